@@ -36,25 +36,18 @@ impl FromStr for Problem {
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
 		let mut lines = input.lines();
-		Ok(Problem {
-			die1: lines
-				.next()
-				.unwrap()
-				.split(' ')
-				.map(|num| num.parse().unwrap())
-				.collect(),
-			die2: lines
-				.next()
-				.unwrap()
-				.split(' ')
-				.map(|num| num.parse().unwrap())
-				.collect(),
-			die3: lines
+		let mut next_die = || -> Result<Vec<usize>, String> {
+			lines
 				.next()
-				.unwrap()
+				.ok_or_else(|| "Missing a die!".to_string())?
 				.split(' ')
-				.map(|num| num.parse().unwrap())
+				.map(|num| num.parse().map_err(|e| format!("Bad die face `{}`: {}", num, e)))
 				.collect()
+		};
+		Ok(Problem {
+			die1: next_die()?,
+			die2: next_die()?,
+			die3: next_die()?
 		})
 	}
 }
@@ -182,4 +175,9 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N);
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2, INPUT_3, INPUT_4]);
+	}
+}
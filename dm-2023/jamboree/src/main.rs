@@ -2,6 +2,7 @@
 
 use std::{
 	io::{stdin, Read},
+	num::ParseIntError,
 	str::FromStr
 };
 
@@ -39,17 +40,20 @@ impl FromStr for Problem {
 		// parse input
 		eprintln!("Parsing input:\n{}", input);
 		let mut lines = input.trim().lines();
-		let (n, m) = lines.next().unwrap().split_once(' ').unwrap();
+		let (n, m) = lines
+			.next()
+			.and_then(|line| line.split_once(' '))
+			.ok_or_else(|| "First line should be `n m`".to_string())?;
 		Ok(Problem {
-			_n:    n.parse().unwrap(),
-			m:     m.parse().unwrap(),
+			_n:    n.parse().map_err(|e: ParseIntError| e.to_string())?,
+			m:     m.parse().map_err(|e: ParseIntError| e.to_string())?,
 			items: lines
 				.next()
-				.unwrap()
+				.ok_or_else(|| "Missing the line of items".to_string())?
 				.trim()
 				.split(' ')
-				.map(|element| element.parse().unwrap())
-				.collect()
+				.map(|element| element.parse().map_err(|e: ParseIntError| e.to_string()))
+				.collect::<Result<_, _>>()?
 		})
 	}
 }
@@ -102,6 +106,8 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
+	use shared::property::{self, ensure};
+
 	use crate::{Problem, Solution};
 
 	impl FromStr for Solution {
@@ -150,4 +156,38 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn carries_at_least_largest_item_and_fair_share() {
+		property::check_where(
+			|rng| {
+				let m: usize = rng.range(1..=20);
+				let items = rng.vec(m..=2 * m, |rng| rng.range(1..=1000));
+				(m, items)
+			},
+			|(m, items)| (*m..=2 * m).contains(&items.len()) && items.iter().all(|&item| item >= 1),
+			|(m, items): &(usize, Vec<usize>)| {
+				let m = *m;
+				let solution: Solution = Problem {
+					_n: items.len(),
+					m,
+					items: items.clone()
+				}
+				.into();
+				let largest = *items.iter().max().unwrap();
+				let fair_share = items.iter().sum::<usize>().div_ceil(m);
+				ensure(solution.max_weight >= largest, || {
+					format!("{} is less than the largest item {}", solution.max_weight, largest)
+				})?;
+				ensure(solution.max_weight >= fair_share, || {
+					format!("{} is less than the fair share {}", solution.max_weight, fair_share)
+				})
+			}
+		);
+	}
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+}
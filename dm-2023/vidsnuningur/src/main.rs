@@ -33,8 +33,15 @@ impl FromStr for Problem {
 
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
+		// the whole line is turned around, spaces inside it too, but not the line ending
 		Ok(Problem {
-			line: input.lines().next().unwrap().trim().to_string()
+			line: input
+				.trim_start_matches('\u{feff}')
+				.lines()
+				.next()
+				.ok_or_else(|| "No line to turn around!".to_string())?
+				.trim_end()
+				.to_string()
 		})
 	}
 }
@@ -64,6 +71,8 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
+	use shared::normalize::{assert_same_when_messy, Whitespace};
+
 	use crate::{Problem, Solution};
 
 	impl FromStr for Solution {
@@ -112,4 +121,24 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+
+	#[test]
+	fn whitespace_does_not_matter() {
+		for input in [INPUT_1, INPUT_2] {
+			assert_same_when_messy(input, Whitespace::Insignificant, |input| {
+				input.parse::<Problem>().map(Solution::from)
+			});
+		}
+	}
+
+	#[test]
+	fn keeps_spaces_inside_line() {
+		let solution: Solution = "ab cd \r\n".parse::<Problem>().unwrap().into();
+		assert_eq!(solution.to_string(), "dc ba");
+	}
+}
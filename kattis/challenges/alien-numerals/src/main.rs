@@ -1,5 +1,7 @@
 #![allow(clippy::many_single_char_names)]
 
+#[cfg(feature = "diagnostics")]
+use shared::diagnostics::{self, Phase};
 use std::{
 	collections::HashMap,
 	fmt::Write,
@@ -16,10 +18,19 @@ fn read_stdin() -> String {
 }
 
 fn main() {
+	// the panic context of `shared::diagnostics` in local runs, submissions are built without it
+	#[cfg(feature = "diagnostics")]
+	diagnostics::install();
 	let input = read_stdin();
 	eprintln!("--- INPUT:");
 	eprintln!("{}", input);
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_input(&input);
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Parsing);
 	let problem: Problem = input.parse().unwrap();
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Solving);
 	let solution: Solution = problem.into();
 	eprintln!("--- OUTPUT:");
 	println!("{}", solution.to_string());
@@ -35,7 +46,9 @@ struct Problem {
 struct Case {
 	alien_number:    String, // the number, can be any combination of human chars
 	source_language: Vec<char>, // char to value, combine to get whole number
-	target_language: Vec<char>  // value to char, combine chars to represent value
+	target_language: Vec<char>, // value to char, combine chars to represent value
+	#[cfg(feature = "diagnostics")]
+	offset:          usize // where its line starts in the input, for the panic context
 }
 
 impl FromStr for Problem {
@@ -43,7 +56,10 @@ impl FromStr for Problem {
 
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
-		let mut lines = input.lines();
+		let mut lines = input.lines().inspect(|_line| {
+			#[cfg(feature = "diagnostics")]
+			diagnostics::point_at(input, _line);
+		});
 		let _case_number = lines
 			.next()
 			.ok_or_else(|| "No first line!".to_string())?
@@ -67,7 +83,9 @@ impl FromStr for Problem {
 					Some(Case {
 						alien_number: alien_number.to_string(),
 						source_language,
-						target_language
+						target_language,
+						#[cfg(feature = "diagnostics")]
+						offset: line.as_ptr() as usize - input.as_ptr() as usize
 					})
 				})
 				.collect::<Option<Vec<_>>>()
@@ -88,7 +106,13 @@ impl From<Problem> for Solution {
 			outputs: problem
 				.cases
 				.into_iter()
-				.map(|case| {
+				.enumerate()
+				.map(|(_index, case)| {
+					#[cfg(feature = "diagnostics")]
+					{
+						diagnostics::set_case(Some(_index + 1));
+						diagnostics::set_offset(case.offset);
+					}
 					eprintln!(
 						"\n> Convert number '{}' in [{}] to [{}]:",
 						case.alien_number,
@@ -205,4 +229,23 @@ mod tests {
 			.into();
 		assert_eq!(solution.to_string(), OUTPUT);
 	}
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT]);
+	}
+
+	#[cfg(feature = "diagnostics")]
+	#[test]
+	fn unknown_digit_points_at_case() {
+		let input = "2\n9 0123456789 oF8\nZZ 0123 xyz\n";
+		shared::diagnostics::install();
+		shared::diagnostics::set_input(input);
+		let problem: Problem = input.parse().unwrap();
+		assert!(std::panic::catch_unwind(|| Solution::from(problem)).is_err());
+		assert_eq!(
+			shared::diagnostics::report().unwrap(),
+			"case #2\nline 3, column 1:\n    3 | ZZ 0123 xyz\n      | ^"
+		);
+	}
+}
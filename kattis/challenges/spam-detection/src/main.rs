@@ -182,4 +182,9 @@ mod tests {
 			assert_eq!(solution.to_string().lines().collect::<String>(), OUTPUT_~N.lines().collect::<String>());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+}
@@ -37,12 +37,15 @@ impl FromStr for Problem {
 
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
-		let mut lines = input.trim().lines();
-		let (p, n) = lines.next().unwrap().split_once(' ').unwrap();
+		// the words have no spaces, so any whitespace between the tokens will do
+		let mut tokens = input.trim_start_matches('\u{feff}').split_whitespace();
+		let (Some(p), Some(n)) = (tokens.next(), tokens.next()) else {
+			return Err("No first line with P and N!".to_string());
+		};
 		Ok(Problem {
-			p:   p.parse().unwrap(),
-			_n:  n.parse().unwrap(),
-			w_i: lines.map(|line| line.to_string()).collect()
+			p:   p.parse().map_err(|e| format!("Bad P: {}", e))?,
+			_n:  n.parse().map_err(|e| format!("Bad N: {}", e))?,
+			w_i: tokens.map(|word| word.to_string()).collect()
 		})
 	}
 }
@@ -91,6 +94,8 @@ impl ToString for Solution {
 mod tests {
 	use std::{num::ParseIntError, str::FromStr};
 
+	use shared::normalize::{assert_same_when_messy, Whitespace};
+
 	use crate::{Problem, Solution, PARADOX_STR};
 
 	impl FromStr for Solution {
@@ -140,4 +145,18 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_SAMPLE_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_SAMPLE_1, INPUT_SAMPLE_2]);
+	}
+
+	#[test]
+	fn whitespace_does_not_matter() {
+		for input in [INPUT_SAMPLE_1, INPUT_SAMPLE_2] {
+			assert_same_when_messy(input, Whitespace::Insignificant, |input| {
+				input.parse::<Problem>().map(Solution::from)
+			});
+		}
+	}
+}
@@ -5,6 +5,8 @@
 // if x falls, y will fall aswell
 // lines (l) tell us which dominos will fall on their own
 
+#[cfg(feature = "diagnostics")]
+use shared::diagnostics::{self, Phase};
 #[allow(unused_imports)]
 use std::{
 	collections::{HashMap, HashSet},
@@ -18,56 +20,276 @@ fn main() {
 	// let mut input = String::new();
 	// io::stdin().lock().read_to_string(&mut input);
 
+	// the panic context of `shared::diagnostics` in local runs, submissions are built without it
+	#[cfg(feature = "diagnostics")]
+	diagnostics::install();
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_input(input);
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Parsing);
+	let cases = parse(input).expect("Cannot parse problem!");
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Solving);
+	for (index, case) in cases.iter().enumerate() {
+		#[cfg(feature = "diagnostics")]
+		diagnostics::set_case(Some(index + 1));
+		println!("{}", topple(case).len());
+	}
+}
+
+// every domino that falls, in the end
+fn topple(case: &Case) -> HashSet<usize> {
+	let mut fallen_dominos = case.fallen_dominos.clone();
+	let mut domino_lineups = case.domino_lineups.clone();
+	eprintln!("--- BEFORE CAUSE-EFFECT ---");
+	eprintln!("fall_set: {:?}", fallen_dominos);
+	eprintln!("knockover_map: {:?}", domino_lineups);
+	let mut changes = true;
+	while changes {
+		changes = false;
+		for key in domino_lineups.clone().keys() {
+			if fallen_dominos.contains(key) {
+				for next_dominos in domino_lineups.remove(key).unwrap() {
+					if fallen_dominos.insert(next_dominos) {
+						changes = true;
+					}
+				}
+			}
+		}
+	}
+	eprintln!("--- AFTER CAUSE-EFFECT ---");
+	eprintln!("fall_set: {:?}", fallen_dominos);
+	eprintln!("knockover_map: {:?}", domino_lineups);
+	fallen_dominos
+}
+
+#[derive(Debug, Default)]
+struct Case {
+	fallen_dominos: HashSet<usize>,            // collected from l lines
+	domino_lineups: HashMap<usize, Vec<usize>> // collected from m lines
+}
+
+// the first line (the number of test cases) is skipped, an empty line gives an empty case
+fn parse(input: &str) -> Result<Vec<Case>, String> {
 	let lines: Vec<_> = input.lines().collect();
+	let number = |n: &str| {
+		n.parse::<usize>()
+			.map_err(|e| format!("Bad number `{}`: {}", n, e))
+	};
+	let mut cases = Vec::new();
 
 	// read structure
 	let mut index = 1;
 	while index < lines.len() {
-		let mut fallen_dominos: HashSet<usize> = HashSet::new(); // collected from l lines
-		let mut domino_lineups: HashMap<usize, Vec<usize>> = HashMap::new(); // collected from m lines
+		let mut case = Case::default();
 
 		let first_line_in_test_case = &lines[index];
+		#[cfg(feature = "diagnostics")]
+		diagnostics::point_at(input, first_line_in_test_case);
 		if first_line_in_test_case.is_empty() {
 			eprintln!("skipped a thing!");
 		} else {
-			let (n, rest) = first_line_in_test_case.split_once(' ').unwrap();
-			let (m, l) = rest.split_once(' ').unwrap();
-			let (n, m, l): (usize, usize, usize) =
-				(n.parse().unwrap(), m.parse().unwrap(), l.parse().unwrap());
-			for _ in 0..m {
+			let mut next_line = || {
 				index += 1;
-				let (x, y) = lines[index].split_once(' ').unwrap();
-				let (x, y): (usize, usize) = (x.parse().unwrap(), y.parse().unwrap());
-				domino_lineups.entry(x).or_default().push(y);
+				let line = lines.get(index).ok_or("Test case ended early!");
+				#[cfg(feature = "diagnostics")]
+				if let Ok(line) = line {
+					diagnostics::point_at(input, line);
+				}
+				line
+			};
+			let (n, rest) = first_line_in_test_case
+				.split_once(' ')
+				.ok_or("Expected n, m and l!")?;
+			let (m, l) = rest.split_once(' ').ok_or("Expected n, m and l!")?;
+			let (n, m, l) = (number(n)?, number(m)?, number(l)?);
+			for _ in 0..m {
+				let (x, y) = next_line()?.split_once(' ').ok_or("Expected x and y!")?;
+				case.domino_lineups.entry(number(x)?).or_default().push(number(y)?);
 			}
 			for _ in 0..l {
-				index += 1;
-				let z = lines[index].parse().unwrap();
-				fallen_dominos.insert(z);
+				case.fallen_dominos.insert(number(next_line()?)?);
 			}
 			eprintln!("n: {}, m: {} l: {}", n, m, l);
 		}
 		index += 1;
+		cases.push(case);
+	}
+	Ok(cases)
+}
 
-		eprintln!("--- BEFORE CAUSE-EFFECT ---");
-		eprintln!("fall_set: {:?}", fallen_dominos);
-		eprintln!("knockover_map: {:?}", domino_lineups);
-		let mut changes = true;
-		while changes {
-			changes = false;
-			for key in domino_lineups.clone().keys() {
-				if fallen_dominos.contains(key) {
-					for next_dominos in domino_lineups.remove(key).unwrap() {
-						if fallen_dominos.insert(next_dominos) {
-							changes = true;
-						}
-					}
+#[cfg(test)]
+mod tests {
+	use std::collections::{BTreeMap, HashSet};
+
+	#[cfg(feature = "diagnostics")]
+	use shared::diagnostics;
+	use shared::{
+		dot::{Dot, Kind, NodeState},
+		graph::{Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	use crate::{parse, topple, Case};
+
+	enum Domino {
+		Fallen,
+		Standing
+	}
+	impl NodeState for Domino {
+		fn color(&self) -> &'static str {
+			match self {
+				Domino::Fallen => "lightsalmon",
+				Domino::Standing => "palegreen"
+			}
+		}
+	}
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_with(Default::default(), &[include_str!("in.txt")], |input| {
+			let _ = parse(input);
+		});
+	}
+
+	#[test]
+	fn sample_parsing() {
+		let cases = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+		let Case {
+			fallen_dominos,
+			domino_lineups
+		} = &cases[0];
+		assert_eq!(fallen_dominos.len(), 1);
+		assert_eq!(domino_lineups[&1], [2]);
+	}
+
+	#[test]
+	fn sample() {
+		let cases = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+		let fallen: Vec<usize> = cases.iter().map(|case| topple(case).len()).collect();
+		let expected: Vec<usize> = include_str!("out.txt")
+			.split_whitespace()
+			.map(|count| count.parse().unwrap())
+			.collect();
+		assert_eq!(fallen, expected);
+	}
+
+	// the dominoes are numbered from 1, up to the highest one in a lineup
+	fn lineup_graph(case: &Case) -> Graph {
+		let lineups = case
+			.domino_lineups
+			.iter()
+			.flat_map(|(&x, ys)| ys.iter().map(move |&y| (x, y)));
+		let dominoes = lineups.clone().map(|(x, y)| x.max(y)).max().unwrap_or(0);
+		Graph::from_one_based(dominoes, Direction::Directed, lineups)
+	}
+
+	#[test]
+	fn graph_of_lineups() {
+		for case in parse(include_str!("in.txt")).expect("Cannot parse problem!") {
+			let graph = lineup_graph(&case);
+			for (x, ys) in case.domino_lineups.iter() {
+				let knocked_over: Vec<usize> =
+					graph.neighbors(x - 1).iter().map(|y| y + 1).collect();
+				assert_eq!(&knocked_over, ys);
+			}
+			assert_eq!(graph.edge_count(), case.domino_lineups.values().map(Vec::len).sum());
+		}
+	}
+
+	// one push for every group of dominoes that nothing else knocks over
+	#[test]
+	fn fewest_pushes_topple_everything() {
+		property::check(
+			|rng| {
+				let dominoes = rng.range(1..=12);
+				let domino = |rng: &mut Rng| rng.range(1..=dominoes);
+				rng.vec(1..=20, |rng| (domino(rng), domino(rng)))
+			},
+			|lineups| {
+				let mut case = Case::default();
+				for &(x, y) in lineups.iter() {
+					case.domino_lineups.entry(x).or_default().push(y);
 				}
+				let graph = lineup_graph(&case);
+				let scc = graph.strongly_connected();
+				let members = scc.members();
+				let pushes: Vec<usize> = scc.sources().iter().map(|&c| members[c][0] + 1).collect();
+				for skipped in None.into_iter().chain(pushes.iter().map(Some)) {
+					case.fallen_dominos =
+						pushes.iter().filter(|&push| Some(push) != skipped).copied().collect();
+					let (fallen, dominoes) = (topple(&case).len(), graph.nodes());
+					ensure((fallen == dominoes) == skipped.is_none(), || {
+						let pushes = &case.fallen_dominos;
+						format!("pushing {:?} topples {} of {}", pushes, fallen, dominoes)
+					})?;
+				}
+				Ok(())
+			}
+		);
+	}
+
+	// a domino falls when it is pushed or anything before it in the condensation falls
+	#[test]
+	fn falling_along_the_condensation() {
+		for case in parse(include_str!("in.txt")).expect("Cannot parse problem!") {
+			let graph = lineup_graph(&case);
+			let scc = graph.strongly_connected();
+			let members = scc.members();
+			let falls = scc
+				.condensation()
+				.fold_dag(
+					|c| members[c].iter().any(|domino| case.fallen_dominos.contains(&(domino + 1))),
+					|&falls, &before, _| falls || before
+				)
+				.expect("The condensation has a cycle!");
+			let mut fallen: HashSet<usize> = (0..graph.nodes())
+				.filter(|&domino| falls[scc.component(domino)])
+				.map(|domino| domino + 1)
+				.collect();
+			// pushed dominoes that are in no lineup
+			fallen.extend(case.fallen_dominos.iter().filter(|&&domino| domino > graph.nodes()));
+			assert_eq!(fallen, topple(&case));
+		}
+	}
+
+	// `case_<n>.dot` in `DOT_DIRECTORY`: what knocks over what, and which dominoes fell in the end
+	#[test]
+	fn knock_over_graph() {
+		let cases = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+		// pushing 2 knocks over 3 as well, 1 stays standing
+		let expected = ["digraph \"dominoes\" {\n    \
+			\"1\" [style=filled, fillcolor=\"palegreen\"];\n    \
+			\"2\" [style=filled, fillcolor=\"lightsalmon\"];\n    \
+			\"3\" [style=filled, fillcolor=\"lightsalmon\"];\n    \
+			\"1\" -> \"2\";\n    \"2\" -> \"3\";\n}\n"];
+		assert_eq!(cases.len(), expected.len());
+		for (index, (case, expected)) in cases.iter().zip(expected).enumerate() {
+			let fallen = topple(case);
+			// in order, the lineups are in a HashMap
+			let lineups: BTreeMap<_, _> = case.domino_lineups.iter().collect();
+			let mut dot = Dot::from_adjacency("dominoes", Kind::Directed, lineups);
+			for domino in case.domino_lineups.keys().chain(fallen.iter()) {
+				let state = if fallen.contains(domino) {
+					Domino::Fallen
+				} else {
+					Domino::Standing
+				};
+				dot.state(domino, &state);
 			}
+			assert_eq!(dot.to_string(), expected);
+			dot.write(&format!("case_{}", index + 1)).expect("Could not write the graph!");
 		}
-		eprintln!("--- AFTER CAUSE-EFFECT ---");
-		eprintln!("fall_set: {:?}", fallen_dominos);
-		eprintln!("knockover_map: {:?}", domino_lineups);
-		println!("{}", fallen_dominos.len());
+	}
+
+	#[cfg(feature = "diagnostics")]
+	#[test]
+	fn parse_error_points_at_line() {
+		let input = "1\n3 2 1\n1 2\n2 x\n3\n";
+		diagnostics::install();
+		diagnostics::set_input(input);
+		assert!(parse(input).is_err());
+		assert_eq!(diagnostics::report().unwrap(), "line 4, column 1:\n    4 | 2 x\n      | ^");
 	}
 }
@@ -0,0 +1,34 @@
+//! Plays the other side for the local judge: `interactor <input> <answer>`,
+//! where the input file holds the secret number.
+
+use std::cmp::Ordering;
+
+use shared::{
+	interactive::Interaction,
+	verdict::{test_case_files, Verdict}
+};
+
+const MAX_GUESSES: usize = 10;
+
+fn main() {
+	let (input, _answer) = test_case_files().expect("Interactor needs the sample files!");
+	let secret: usize = input.trim().parse().expect("Input should be the secret number!");
+	let mut interaction = Interaction::stdio();
+	for _ in 0..MAX_GUESSES {
+		let guess: usize = interaction.read().unwrap_or_else(|e| {
+			Verdict::WrongAnswer(format!("Could not read a guess: {}", e)).exit()
+		});
+		let reply = match guess.cmp(&secret) {
+			Ordering::Less => "higher",
+			Ordering::Greater => "lower",
+			Ordering::Equal => "correct"
+		};
+		if interaction.send(reply).is_err() {
+			Verdict::WrongAnswer("Solution stopped listening".into()).exit()
+		}
+		if guess == secret {
+			Verdict::Accepted.exit()
+		}
+	}
+	Verdict::WrongAnswer(format!("No correct guess in {} tries", MAX_GUESSES)).exit()
+}
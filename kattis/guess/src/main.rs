@@ -0,0 +1,74 @@
+use std::io::{stdin, stdout, BufRead, Write};
+
+fn main() {
+	guess(stdin().lock(), stdout().lock());
+}
+
+// binary search over 1..=1000, 10 guesses are always enough
+fn guess(mut replies: impl BufRead, mut guesses: impl Write) {
+	let (mut low, mut high) = (1, 1000);
+	loop {
+		let guess = (low + high) / 2;
+		writeln!(guesses, "{}", guess).expect("Could not write guess?");
+		guesses.flush().expect("Could not flush guess?");
+		let mut reply = String::new();
+		replies
+			.read_line(&mut reply)
+			.expect("Could not read reply?");
+		match reply.trim() {
+			"lower" => high = guess - 1,
+			"higher" => low = guess + 1,
+			"correct" => break,
+			unknown => panic!("Unknown reply: {}", unknown)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{pipe, BufRead, BufReader, Write};
+
+	use seq_macro::seq;
+
+	use crate::guess;
+
+	fn play(secret: usize) -> usize {
+		let (guess_reader, guess_writer) = pipe().unwrap();
+		let (reply_reader, mut reply_writer) = pipe().unwrap();
+		let solver = std::thread::spawn(move || guess(BufReader::new(reply_reader), guess_writer));
+		let mut guesses = BufReader::new(guess_reader);
+		let mut count = 0;
+		loop {
+			let mut line = String::new();
+			guesses.read_line(&mut line).unwrap();
+			count += 1;
+			let guess: usize = line.trim().parse().unwrap();
+			let reply = match guess.cmp(&secret) {
+				std::cmp::Ordering::Less => "higher",
+				std::cmp::Ordering::Greater => "lower",
+				std::cmp::Ordering::Equal => "correct"
+			};
+			writeln!(reply_writer, "{}", reply).unwrap();
+			if guess == secret {
+				break;
+			}
+		}
+		solver.join().unwrap();
+		count
+	}
+
+	seq!(N in 1..=3 {
+		#[test]
+		fn sample_~N() {
+			let secret = include_str!(concat!(N, ".in")).trim().parse().unwrap();
+			assert!(play(secret) <= 10);
+		}
+	});
+
+	#[test]
+	fn every_number() {
+		for secret in 1..=1000 {
+			assert!(play(secret) <= 10, "too many guesses for {}", secret);
+		}
+	}
+}
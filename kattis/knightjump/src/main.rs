@@ -138,14 +138,24 @@ impl FromStr for Problem {
 			.parse::<usize>()
 			.map_err(|e| e.to_string())?;
 
+		let rows: Vec<Vec<char>> = lines.map(|line| line.chars().collect()).collect();
+		if rows.len() != n {
+			return Err(format!("Expected {} rows, found {}", n, rows.len()));
+		}
 		let mut chess_board = ChessBoard {
 			size: n,
 			data: vec![BoardData::Empty; n * n]
 		};
 		let mut initial_knight_pos = None;
-		for (y, line) in lines.enumerate() {
-			let characters: Vec<_> = line.chars().collect();
-			assert_eq!(characters.len(), n);
+		for (y, characters) in rows.into_iter().enumerate() {
+			if characters.len() != n {
+				return Err(format!(
+					"Expected {} squares in row {}, found {}",
+					n,
+					y + 1,
+					characters.len()
+				));
+			}
 			for (x, character) in characters.into_iter().enumerate() {
 				let pos = Position {
 					x: x as isize,
@@ -166,7 +176,8 @@ impl FromStr for Problem {
 
 		Ok(Problem {
 			chess_board,
-			initial_knight_pos: initial_knight_pos.ok_or_else(|| "".to_string())?
+			initial_knight_pos: initial_knight_pos
+				.ok_or_else(|| "No knight on the board".to_string())?
 		})
 	}
 }
@@ -181,45 +192,56 @@ enum Solution {
 
 impl From<Problem> for Solution {
 	fn from(problem: Problem) -> Self {
-		// solve problem
-		let mut board = problem.chess_board;
-		let mut queue = vec![problem.initial_knight_pos];
-		let mut iteration = 1;
-		let found_answer = 'bfs: loop {
-			let mut new_queue = Vec::new();
-			if queue.is_empty() {
-				break 'bfs false;
-			}
+		jump(problem, |_, _| {})
+	}
+}
 
-			for knight_position in queue.into_iter() {
-				let mut legal_new_positions = KNIGHT_POSSIBLE_MOVES
-					.into_iter()
-					.filter_map(|m| {
-						let possible_pos = knight_position.clone() + m.clone();
-						if board.try_occupy(&possible_pos) {
-							Some(possible_pos)
-						} else {
-							None
-						}
-					})
-					.collect::<Vec<_>>();
-
-				for legal_new_pos in legal_new_positions.iter() {
-					if *legal_new_pos == TARGET_POS {
-						break 'bfs true; // found answer!
+// the BFS, telling `layer` about every new layer of positions with its distance from the knight
+fn jump(problem: Problem, mut layer: impl FnMut(usize, &[Position])) -> Solution {
+	layer(0, std::slice::from_ref(&problem.initial_knight_pos));
+	if problem.initial_knight_pos == TARGET_POS {
+		return Solution::Steps(0);
+	}
+	let mut board = problem.chess_board;
+	let mut queue = vec![problem.initial_knight_pos];
+	let mut iteration = 1;
+	let found_answer = 'bfs: loop {
+		let mut new_queue = Vec::new();
+		if queue.is_empty() {
+			break 'bfs false;
+		}
+
+		for knight_position in queue.into_iter() {
+			let mut legal_new_positions = KNIGHT_POSSIBLE_MOVES
+				.into_iter()
+				.filter_map(|m| {
+					let possible_pos = knight_position.clone() + m.clone();
+					if board.try_occupy(&possible_pos) {
+						Some(possible_pos)
+					} else {
+						None
 					}
-				}
-				new_queue.append(&mut legal_new_positions);
+				})
+				.collect::<Vec<_>>();
+
+			let found = legal_new_positions.contains(&TARGET_POS);
+			new_queue.append(&mut legal_new_positions);
+			if found {
+				layer(iteration, &new_queue);
+				break 'bfs true; // found answer!
 			}
-			iteration += 1;
-			queue = new_queue;
-		};
-
-		if found_answer {
-			Solution::Steps(iteration)
-		} else {
-			Solution::Unsolvable
 		}
+		if !new_queue.is_empty() {
+			layer(iteration, &new_queue);
+		}
+		iteration += 1;
+		queue = new_queue;
+	};
+
+	if found_answer {
+		Solution::Steps(iteration)
+	} else {
+		Solution::Unsolvable
 	}
 }
 
@@ -237,7 +259,15 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
-	use crate::{Problem, Solution};
+	use shared::{
+		fuzz,
+		grid::{Grid, Point, KNIGHT},
+		property::{self, ensure, Shrink},
+		search::{astar, bfs, knight_distance},
+		visual::{Cell, Frame, Replay}
+	};
+
+	use crate::{jump, BoardData, Position, Problem, Solution};
 
 	impl FromStr for Solution {
 		type Err = String;
@@ -285,4 +315,152 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+
+	#[test]
+	fn bfs_replay() {
+		for (name, input) in [("sample_1", INPUT_1), ("sample_2", INPUT_2)] {
+			let problem: Problem = input.parse().expect("Cannot parse problem!");
+			let n = problem.chess_board.size;
+			let blocked = Grid::from_fn(n, n, |Point { x, y }| {
+				let data = problem.chess_board.get_board_data(&Position { x, y });
+				matches!(data, Some(BoardData::Blocked))
+			});
+			let knight = Point::new(problem.initial_knight_pos.x, problem.initial_knight_pos.y);
+			let mut distances = Grid::new(n, n, None);
+			let mut replay = Replay::new();
+			let solution = jump(problem, |distance, layer| {
+				for position in layer {
+					distances[Point::new(position.x, position.y)] = Some(distance);
+				}
+				let title = format!("{} jumps", distance);
+				replay.push(Frame::from_grid(title, &distances, |point, distance| {
+					match distance {
+						_ if point == knight => Cell::Marked('K'),
+						Some(distance) => Cell::Distance(*distance),
+						None if blocked[point] => Cell::Plain('#'),
+						None => Cell::Plain('.')
+					}
+				}));
+			});
+			// one frame per layer, the last one reaching the top left corner if it can be reached
+			match solution {
+				Solution::Steps(steps) => {
+					assert_eq!(replay.frames.len(), steps + 1);
+					assert_eq!(replay.frames[steps].rows[0][0], Cell::Distance(steps));
+				},
+				Solution::Unsolvable => assert!(distances[Point::new(0, 0)].is_none())
+			}
+			replay.show(name).expect("Cannot show the replay!");
+		}
+	}
+
+	#[derive(Debug, Clone)]
+	struct Board {
+		blocked: Grid<bool>,
+		knight:  Point
+	}
+	impl Shrink for Board {
+		// unblock one square at a time
+		fn shrink(&self) -> Vec<Self> {
+			self.blocked
+				.iter()
+				.filter(|(_, &blocked)| blocked)
+				.map(|(point, _)| {
+					let mut board = self.clone();
+					board.blocked[point] = false;
+					board
+				})
+				.collect()
+		}
+	}
+	impl Board {
+		fn input(&self) -> String {
+			let mut squares = self.blocked.map(|_, &blocked| if blocked { '#' } else { '.' });
+			squares[self.knight] = 'K';
+			format!("{}\n{}", self.blocked.width(), squares)
+		}
+
+		// plain BFS from the knight to the top left corner
+		fn distance(&self) -> Option<usize> {
+			let blocked = &self.blocked;
+			bfs(
+				[self.knight],
+				|&square| blocked.neighbors(square, &KNIGHT).filter(|&next| !blocked[next]),
+				|&square| square == Point::new(0, 0)
+			)
+			.distance_to_goal()
+		}
+	}
+
+	#[test]
+	fn astar_on_a_big_board() {
+		// the largest board, empty but for a wall the knight has to go around
+		let n = 100;
+		let mut blocked = Grid::new(n, n, false);
+		for x in 0..n as isize - 2 {
+			blocked[Point::new(x, 50)] = true;
+		}
+		let board = Board {
+			blocked,
+			knight: Point::new(n as isize - 1, n as isize - 1)
+		};
+		let blocked = &board.blocked;
+		let target = Point::new(0, 0);
+		let neighbors = |&square: &Point| {
+			blocked
+				.neighbors(square, &KNIGHT)
+				.filter(|&next| !blocked[next])
+				.collect::<Vec<_>>()
+		};
+		let bfs = bfs([board.knight], neighbors, |&square| square == target);
+		let astar = astar(
+			board.knight,
+			|square| neighbors(square).into_iter().map(|next| (next, 1)),
+			|&square| knight_distance(square, target),
+			|&square| square == target
+		);
+		let solution: Solution = board.input().parse::<Problem>().unwrap().into();
+		assert_eq!(solution, Solution::Steps(astar.distance_to_goal().unwrap()));
+		assert_eq!(astar.distance_to_goal(), bfs.distance_to_goal());
+		assert!(
+			astar.expanded() * 10 < bfs.expanded(),
+			"A* expanded {} squares, BFS {}",
+			astar.expanded(),
+			bfs.expanded()
+		);
+	}
+
+	#[test]
+	fn steps_equal_bfs_distance() {
+		property::check(
+			|rng| {
+				let n = rng.range(1..=8);
+				let mut blocked = Grid::from_fn(n, n, |_| rng.chance(0.3));
+				blocked[Point::new(0, 0)] = false; // the target is never blocked
+				let mut coordinate = || rng.range(0..=n - 1) as isize;
+				let knight = Point::new(coordinate(), coordinate());
+				blocked[knight] = false;
+				Board { blocked, knight }
+			},
+			|board: &Board| {
+				let solution: Solution = board
+					.input()
+					.parse::<Problem>()
+					.map_err(|e| format!("could not parse {:?}: {}", board.input(), e))?
+					.into();
+				let expected = match board.distance() {
+					Some(steps) => Solution::Steps(steps),
+					None => Solution::Unsolvable
+				};
+				ensure(solution == expected, || {
+					format!("expected {:?}, got {:?} for\n{}", expected, solution, board.input())
+				})
+			}
+		);
+	}
+}
@@ -111,4 +111,9 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_0, INPUT_1]);
+	}
+}
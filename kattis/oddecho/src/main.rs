@@ -34,16 +34,18 @@ impl FromStr for Problem {
 
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
-		let mut lines = input.lines();
-		Ok(Problem {
-			_n:      lines
-				.next()
-				.ok_or_else(|| "".to_string())?
-				.parse::<u8>()
-				.map_err(|e| e.to_string())
-				.unwrap(),
-			n_lines: lines.map(|line| line.to_string()).collect()
-		})
+		// a word has no spaces, so any whitespace between them will do
+		let mut tokens = input.trim_start_matches('\u{feff}').split_whitespace();
+		let n = tokens
+			.next()
+			.ok_or_else(|| "Missing the amount of lines".to_string())?
+			.parse::<u8>()
+			.map_err(|e| e.to_string())?;
+		let n_lines: Vec<String> = tokens.take(n as usize).map(|word| word.to_string()).collect();
+		if n_lines.len() != n as usize {
+			return Err(format!("Expected {} lines, found {}", n, n_lines.len()));
+		}
+		Ok(Problem { _n: n, n_lines })
 	}
 }
 
@@ -77,6 +79,8 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
+	use shared::normalize::{assert_same_when_messy, Whitespace};
+
 	use crate::{Problem, Solution};
 
 	impl FromStr for Solution {
@@ -124,4 +128,18 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+
+	#[test]
+	fn whitespace_does_not_matter() {
+		for input in [INPUT_1, INPUT_2] {
+			assert_same_when_messy(input, Whitespace::Insignificant, |input| {
+				input.parse::<Problem>().map(Solution::from)
+			});
+		}
+	}
+}
@@ -9,84 +9,118 @@ fn main() {
 }
 
 fn solve(input: String) -> Vec<usize> {
+    parse(&input)
+        .expect("Cannot parse problem!")
+        .into_iter()
+        .map(find_min_roads_needed)
+        .collect()
+}
+
+// well above the limit of the problem, but keeps a broken count from allocating everything
+const MAX_ENDPOINTS: usize = 100_000;
+
+// every city as a list of connections per endpoint
+fn parse(input: &str) -> Result<Vec<Vec<Vec<usize>>>, String> {
     let mut lines = input.lines();
-    let city_count = lines.next().unwrap().parse::<usize>().unwrap();
-    let mut roads_needed_per_city: Vec<usize> = Vec::new();
+    let next_number = |lines: &mut std::str::Lines| -> Result<usize, String> {
+        let line = lines.next().ok_or("Input ended early!")?.trim();
+        line.parse::<usize>()
+            .map_err(|e| format!("Bad number `{}`: {}", line, e))
+    };
+    let city_count = next_number(&mut lines)?;
+    let mut cities = Vec::new();
     for _city_num in 0..city_count {
         // parse city
-        let endpoint_count = lines.next().unwrap().trim().parse::<usize>().unwrap();
-        let existing_road_count = lines.next().unwrap().trim().parse::<usize>().unwrap();
+        let endpoint_count = next_number(&mut lines)?;
+        let existing_road_count = next_number(&mut lines)?;
+        if endpoint_count == 0 || endpoint_count > MAX_ENDPOINTS {
+            return Err(format!("A city cannot have {} endpoints", endpoint_count));
+        }
         let mut endpoints: Vec<Vec<usize>> = vec![vec![]; endpoint_count];
         for _road_num in 0..existing_road_count {
             // parse road
-            let (endpoint_a, endpoint_b) = lines.next().unwrap().split_once(' ').unwrap();
-            let (a, b) = (
-                endpoint_a.parse::<usize>().unwrap(),
-                endpoint_b.parse::<usize>().unwrap(),
-            );
+            let line = lines.next().ok_or("Missing a road!")?;
+            let (endpoint_a, endpoint_b) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("Expected two endpoints in `{}`", line))?;
+            let endpoint = |endpoint: &str| {
+                endpoint
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&index| index < endpoint_count)
+                    .ok_or_else(|| format!("Bad endpoint `{}`", endpoint))
+            };
+            let (a, b) = (endpoint(endpoint_a)?, endpoint(endpoint_b)?);
             endpoints[a].push(b);
             endpoints[b].push(a);
         }
+        cities.push(endpoints);
+    }
+    Ok(cities)
+}
 
-        // solve for needed road count in city
-        roads_needed_per_city.push(find_min_roads_needed(endpoints));
-    }
-
-    fn find_min_roads_needed(city: Vec<Vec<usize>>) -> usize {
-        let mut unions: Vec<Vec<usize>> = vec![];
-        for (my_index, connections) in city.into_iter().enumerate() {
-            let union_indexes_with_me = unions
-                .iter()
-                .enumerate()
-                .filter_map(|(union_index, union)| {
-                    if union.contains(&my_index) {
-                        Some(union_index)
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<usize>>();
-            match union_indexes_with_me.len() {
-                0 => {
-                    // add a union with me and my connections
-                    let mut new_union = connections;
-                    new_union.push(my_index);
-                    unions.push(new_union);
-                }
-                1 => {
-                    // add my connections if missing
-                    debug_assert_eq!(union_indexes_with_me.len(), 1);
-                    let my_union_index = union_indexes_with_me.first().unwrap();
-                    let my_union = &mut unions[*my_union_index];
-                    for connection in connections {
-                        if !my_union.contains(&connection) {
-                            my_union.push(connection);
-                        }
-                    }
-                }
-                _multiple => {
-                    // connect multiple unions into one
-                    for union_index_window in union_indexes_with_me.windows(2) {
-                        let left = unions.remove(union_index_window[0]);
-                        let right = &mut unions[union_index_window[1]];
-                        for element in left.into_iter() {
-                            if !right.contains(&element) {
-                                right.push(element);
-                            }
-                        }
-                    }
-                }
-            }
+fn find_min_roads_needed(city: Vec<Vec<usize>>) -> usize {
+    let mut endpoints = UnionFind::new(city.len());
+    for (a, connections) in city.into_iter().enumerate() {
+        for b in connections {
+            endpoints.union(a, b);
         }
+    }
+    endpoints.count_sets() - 1
+}
 
-        unions.len() - 1
+// the endpoints that can already reach each other, as disjoint sets
+struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+            sets: n,
+        }
     }
 
-    roads_needed_per_city
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            // point every other endpoint on the way at its grandparent
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        self.sets -= 1;
+    }
+
+    fn count_sets(&self) -> usize {
+        self.sets
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use shared::{
+        dot::{palette, Dot, Kind},
+        graph::{dsu::Dsu, Direction, Graph},
+        property::{self, ensure},
+        random::Rng
+    };
+
     use crate::*;
 
     fn parse_out(input: impl AsRef<str>) -> Vec<usize> {
@@ -103,4 +137,169 @@ mod tests {
         let expectation = parse_out(include_str!("out.txt"));
         assert_eq!(result, expectation);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn components_of_sample() {
+        let cities = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+        let components: Vec<usize> = cities
+            .iter()
+            .map(|city| city_graph(city).components().count() - 1)
+            .collect();
+        assert_eq!(components, parse_out(include_str!("out.txt")));
+    }
+
+    fn dsu_roads_needed(city: &[Vec<usize>]) -> usize {
+        let mut endpoints = Dsu::new(city.len());
+        for (a, connections) in city.iter().enumerate() {
+            for &b in connections {
+                endpoints.union(a, b);
+            }
+        }
+        endpoints.count_sets() - 1
+    }
+
+    #[test]
+    fn dsu_on_sample() {
+        let cities = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+        let needed: Vec<usize> = cities.iter().map(|city| dsu_roads_needed(city)).collect();
+        assert_eq!(needed, parse_out(include_str!("out.txt")));
+    }
+
+    #[test]
+    fn dsu_on_random_cities() {
+        property::check(
+            |rng| {
+                let endpoints = rng.range(1..=12);
+                let roads = rng.range(0..=endpoints * (endpoints - 1) / 2);
+                let roads: Vec<String> = rng
+                    .graph(endpoints, roads)
+                    .into_iter()
+                    .map(|(a, b)| format!("{} {}", a, b))
+                    .collect();
+                format!("1\n{}\n{}\n{}\n", endpoints, roads.len(), roads.join("\n"))
+            },
+            |input: &String| {
+                let city = parse(input)?.remove(0);
+                let expected = dsu_roads_needed(&city);
+                let needed = find_min_roads_needed(city);
+                ensure(needed == expected, || format!("{} roads instead of {}", needed, expected))
+            }
+        );
+    }
+
+    // at the limit, every endpoint alone and all of them in one chain
+    #[test]
+    fn big_cities() {
+        let endpoints = MAX_ENDPOINTS;
+        let chain: Vec<String> = (1..endpoints).map(|b| format!("{} {}", b - 1, b)).collect();
+        let input = format!(
+            "2\n{}\n0\n{}\n{}\n{}\n",
+            endpoints,
+            endpoints,
+            chain.len(),
+            chain.join("\n")
+        );
+        assert_eq!(solve(input), vec![endpoints - 1, 0]);
+    }
+
+    // a road is critical when the city needs one more road without it
+    #[test]
+    fn critical_roads() {
+        property::check(
+            |rng| {
+                let endpoints = rng.range(1..=10);
+                let endpoint = |rng: &mut Rng| rng.range(0..=endpoints - 1);
+                (endpoints, rng.vec(0..=12, |rng| (endpoint(rng), endpoint(rng))))
+            },
+            |(endpoints, roads)| {
+                let roads: Vec<(usize, usize)> = roads
+                    .iter()
+                    .copied()
+                    .filter(|&(a, b)| a < *endpoints && b < *endpoints)
+                    .collect();
+                let needed = |skipped: Option<usize>| -> Result<usize, String> {
+                    let lines: Vec<String> = (0..roads.len())
+                        .filter(|&road| Some(road) != skipped)
+                        .map(|road| format!("{} {}", roads[road].0, roads[road].1))
+                        .collect();
+                    let roads = lines.join("\n");
+                    let input = format!("1\n{}\n{}\n{}\n", endpoints, lines.len(), roads);
+                    Ok(find_min_roads_needed(parse(&input)?.remove(0)))
+                };
+                let graph = Graph::from_edges(*endpoints, Direction::Undirected, roads.clone());
+                let cuts = graph.cuts();
+                let all_roads = needed(None)?;
+                for (road, &(a, b)) in roads.iter().enumerate() {
+                    let critical = needed(Some(road))? == all_roads + 1;
+                    ensure(cuts.is_bridge(road) == critical, || {
+                        format!("road {} {}: critical {}", a, b, critical)
+                    })?;
+                }
+                Ok(())
+            }
+        );
+    }
+
+    // every road is in both lists already, so the graph gets them as directed edges
+    fn city_graph(city: &[Vec<usize>]) -> Graph {
+        let roads = city
+            .iter()
+            .enumerate()
+            .flat_map(|(a, connections)| connections.iter().map(move |&b| (a, b)));
+        Graph::from_edges(city.len(), Direction::Directed, roads)
+    }
+
+    #[test]
+    fn parser_never_panics() {
+        shared::fuzz::check_with(Default::default(), &[include_str!("in.txt")], |input| {
+            let _ = parse(input);
+        });
+    }
+
+    // `city_<n>.dot` in `DOT_DIRECTORY`, with every union in its own color
+    #[test]
+    fn union_graphs() {
+        let cities = parse(include_str!("in.txt")).expect("Cannot parse problem!");
+        let colored = |node: usize, color: &str| {
+            format!("    \"{}\" [style=filled, fillcolor=\"{}\"];\n", node, color)
+        };
+        let expected = [
+            format!(
+                "graph \"city\" {{\n{}{}{}{}{}    \"0\" -- \"1\";\n    \"1\" -- \"2\";\n    \
+                 \"3\" -- \"4\";\n}}\n",
+                colored(0, "lightblue"),
+                colored(1, "lightblue"),
+                colored(2, "lightblue"),
+                colored(3, "palegreen"),
+                colored(4, "palegreen")
+            ),
+            format!(
+                "graph \"city\" {{\n{}{}    \"0\" -- \"1\";\n}}\n",
+                colored(0, "lightblue"),
+                colored(1, "lightblue")
+            ),
+        ];
+        assert_eq!(cities.len(), expected.len());
+        for (index, (city, expected)) in cities.into_iter().zip(expected).enumerate() {
+            let graph = city_graph(&city);
+            for (endpoint, connections) in city.iter().enumerate() {
+                assert_eq!(graph.neighbors(endpoint), connections);
+            }
+            let mut dot = Dot::from_adjacency("city", Kind::Undirected, graph.adjacency());
+            let mut endpoints = Dsu::new(city.len());
+            for (a, connections) in city.iter().enumerate() {
+                for &b in connections {
+                    endpoints.union(a, b);
+                }
+            }
+            for (union_index, union) in endpoints.groups().iter().enumerate() {
+                for endpoint in union {
+                    dot.color(endpoint, palette(union_index));
+                }
+            }
+            assert_eq!(dot.to_string(), expected);
+            dot.write(&format!("city_{}", index + 1))
+                .expect("Could not write the graph!");
+        }
+    }
+}
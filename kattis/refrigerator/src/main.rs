@@ -1,5 +1,8 @@
 #![allow(clippy::many_single_char_names)]
 
+#[cfg(test)]
+mod validator;
+
 use std::{
 	io::{stdin, Read},
 	str::FromStr
@@ -179,7 +182,12 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
-	use crate::{Problem, Solution};
+	use shared::{
+		validator::{assert_accepted, Validator},
+		verdict::Verdict
+	};
+
+	use crate::{validator::OutputValidator, Problem, Solution};
 
 	impl FromStr for Solution {
 		type Err = String;
@@ -237,5 +245,31 @@ mod tests {
 				 .into();
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
+
+		#[test]
+		fn output_accepted_by_validator_~N() {
+			let solution: Solution = INPUT_~N
+				 .parse::<Problem>()
+				 .expect("Cannot parse problem!")
+				 .into();
+			assert_accepted::<OutputValidator>(INPUT_~N, OUTPUT_~N, &solution.to_string());
+		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn validator_accepts_other_optimal_trips() {
+		// both cars cost 10 per refrigerator
+		assert_accepted::<OutputValidator>("10 1 20 2 2", "0 1 20", "2 0 20");
+		// 2^63 + 2 trips at 10 each wrap around to a cost of 20
+		let wrapping = format!("{} 0 20", (1_usize << 63) + 2);
+		for wrong in ["1 0 10", "2 0 30", "3 0 30", "2 0", "two 0 20", &wrapping] {
+			let verdict = OutputValidator::validate("10 1 20 2 2", "0 1 20", wrong);
+			assert!(verdict != Verdict::Accepted, "accepted {:?}", wrong);
+		}
+	}
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1]);
+	}
+}
@@ -0,0 +1,53 @@
+use shared::{validator::Validator, verdict::Verdict};
+
+// any trips that fit all refrigerators at the lowest cost, there can be several
+pub struct OutputValidator;
+impl Validator for OutputValidator {
+	fn validate(input: &str, answer: &str, output: &str) -> Verdict {
+		let numbers = |text: &str| -> Result<Vec<usize>, String> {
+			text.split_whitespace()
+				.map(|n| n.parse().map_err(|_| format!("`{}` is not a number", n)))
+				.collect()
+		};
+		let input = numbers(input).unwrap();
+		let (a_cost, a_capacity, b_cost, b_capacity, refrigerators) =
+			(input[0], input[1], input[2], input[3], input[4]);
+		let lowest_cost = numbers(answer).unwrap()[2];
+
+		let output = match numbers(output) {
+			Ok(output) if output.len() == 3 => output,
+			Ok(output) => {
+				return Verdict::WrongAnswer(format!("expected 3 numbers, found {}", output.len()));
+			},
+			Err(reason) => return Verdict::WrongAnswer(reason)
+		};
+		let (a_trips, b_trips, total_cost) = (output[0], output[1], output[2]);
+		// the trips come from the contestant, so they can be big enough to wrap around
+		let both = |a: usize, b: usize| {
+			a_trips
+				.checked_mul(a)
+				.zip(b_trips.checked_mul(b))
+				.and_then(|(a, b)| a.checked_add(b))
+		};
+		let (Some(moved), Some(cost)) = (both(a_capacity, b_capacity), both(a_cost, b_cost)) else {
+			let trips = format!("{} and {} trips are far too many", a_trips, b_trips);
+			return Verdict::WrongAnswer(trips);
+		};
+		if moved < refrigerators {
+			return Verdict::WrongAnswer(format!(
+				"trips only fit {} of {} refrigerators",
+				moved, refrigerators
+			));
+		}
+		if total_cost != cost {
+			return Verdict::WrongAnswer(format!("trips cost {}, not {}", cost, total_cost));
+		}
+		if total_cost != lowest_cost {
+			return Verdict::WrongAnswer(format!(
+				"cost {} is not the lowest, {}",
+				total_cost, lowest_cost
+			));
+		}
+		Verdict::Accepted
+	}
+}
@@ -36,10 +36,13 @@ impl FromStr for Problem {
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
 		let mut lines = input.lines();
-		let (_n, x) = lines.next().unwrap().split_once(' ').unwrap();
+		let (_n, x) = lines
+			.next()
+			.and_then(|line| line.split_once(' '))
+			.ok_or_else(|| "First line should be `n x`".to_string())?;
 		Ok(Problem {
-			_n: _n.parse().unwrap(),
-			x:  x.parse().unwrap(),
+			_n: _n.parse::<usize>().map_err(|e| e.to_string())?,
+			x:  x.parse::<usize>().map_err(|e| e.to_string())?,
 			a:  lines
 				.map(|line| line.parse::<usize>().map_err(|e| e.to_string()))
 				.collect::<Result<_, Self::Err>>()?
@@ -132,4 +135,9 @@ mod tests {
 			assert_eq!(solution.to_string(), OUTPUT_~N.trim());
 		}
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2]);
+	}
+}
@@ -0,0 +1,8 @@
+//! Output validator for the local judge, see `src/validator.rs`.
+
+#[path = "../validator.rs"]
+mod validator;
+
+fn main() {
+	shared::validator::run::<validator::OutputValidator>()
+}
@@ -1,7 +1,9 @@
 #![allow(clippy::many_single_char_names)]
 
+#[cfg(test)]
+mod validator;
+
 use std::{
-	collections::HashSet,
 	io::{stdin, Read},
 	str::FromStr
 };
@@ -36,9 +38,16 @@ impl FromStr for Problem {
 	fn from_str(input: &str) -> Result<Self, Self::Err> {
 		// parse input
 		let mut lines = input.lines();
+		let mut next_number = |name: &str| {
+			lines
+				.next()
+				.ok_or_else(|| format!("Missing {}", name))?
+				.parse::<usize>()
+				.map_err(|e| format!("Could not read {}: {}", name, e))
+		};
 		Ok(Problem {
-			n: lines.next().unwrap().parse().unwrap(),
-			m: lines.next().unwrap().parse().unwrap()
+			n: next_number("n")?,
+			m: next_number("m")?
 		})
 	}
 }
@@ -49,18 +58,13 @@ struct Solution {
 }
 impl PartialEq for Solution {
 	fn eq(&self, other: &Self) -> bool {
-		// same length and same elements
-		self.team_placement.len() == other.team_placement.len() && {
-			self.team_placement
-				.iter()
-				.cloned()
-				.collect::<HashSet<usize>>()
-				== self
-					.team_placement
-					.iter()
-					.cloned()
-					.collect::<HashSet<usize>>()
-		}
+		// same room sizes, in any order
+		let sorted = |placement: &Vec<usize>| {
+			let mut placement = placement.clone();
+			placement.sort();
+			placement
+		};
+		sorted(&self.team_placement) == sorted(&other.team_placement)
 	}
 }
 impl From<Problem> for Solution {
@@ -94,7 +98,13 @@ impl ToString for Solution {
 mod tests {
 	use std::str::FromStr;
 
-	use crate::{Problem, Solution};
+	use shared::{
+		property,
+		validator::{assert_accepted, Validator},
+		verdict::Verdict
+	};
+
+	use crate::{validator::OutputValidator, Problem, Solution};
 
 	impl FromStr for Solution {
 		type Err = String;
@@ -132,6 +142,15 @@ mod tests {
 			assert_eq!(solution, expectation);
 		}
 
+		#[test]
+		fn output_accepted_by_validator_~N() {
+			let solution: Solution = INPUT_~N
+				 .parse::<Problem>()
+				 .expect("Cannot parse problem!")
+				 .into();
+			assert_accepted::<OutputValidator>(INPUT_~N, OUTPUT_~N, &solution.to_string());
+		}
+
 		// #[test]
 		// fn output_matches_sample_~N() {
 		// 	let solution: Solution = INPUT_~N
@@ -141,4 +160,39 @@ mod tests {
 		// 	assert_eq!(solution.to_string(), OUTPUT_~N);
 		// }
 	});
-}
\ No newline at end of file
+
+	#[test]
+	fn validator_rejects() {
+		assert!(Solution { team_placement: vec![2, 1] } != Solution { team_placement: vec![3, 0] });
+		assert_accepted::<OutputValidator>("2\n3\n", "**\n*\n", "*\n**\n");
+		for wrong in ["***\n", "***\n\n", "*\n*\n", "**\n-\n", "***\n*\n"] {
+			let verdict = OutputValidator::validate("2\n4\n", "", wrong);
+			assert!(verdict != Verdict::Accepted, "accepted {:?}", wrong);
+		}
+	}
+
+	#[test]
+	fn rooms_sum_to_m_and_differ_by_at_most_one() {
+		property::check_where(
+			|rng| {
+				let n = rng.range(1..=100);
+				(n, rng.range(n..=100))
+			},
+			|&(n, m)| n >= 1 && m >= n,
+			|&(n, m): &(usize, usize)| {
+				let solution: Solution = Problem { n, m }.into();
+				let input = format!("{}\n{}\n", n, m);
+				// the validator checks exactly these invariants
+				match OutputValidator::validate(&input, "", &solution.to_string()) {
+					Verdict::Accepted => Ok(()),
+					Verdict::WrongAnswer(reason) => Err(reason)
+				}
+			}
+		);
+	}
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_1, INPUT_2, INPUT_3, INPUT_4]);
+	}
+}
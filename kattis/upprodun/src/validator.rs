@@ -0,0 +1,36 @@
+use shared::{validator::Validator, verdict::Verdict};
+
+// any placement where every room is used and no room has more than one team above another
+pub struct OutputValidator;
+impl Validator for OutputValidator {
+	fn validate(input: &str, _answer: &str, output: &str) -> Verdict {
+		let mut numbers = input.split_whitespace().map(|n| n.parse::<usize>().unwrap());
+		let (rooms, teams) = (numbers.next().unwrap(), numbers.next().unwrap());
+
+		let lines: Vec<&str> = output.trim_end().lines().collect();
+		if lines.len() != rooms {
+			return Verdict::WrongAnswer(format!(
+				"expected {} rooms, found {}",
+				rooms,
+				lines.len()
+			));
+		}
+		let not_teams = |line: &&&str| line.is_empty() || line.chars().any(|c| c != '*');
+		if let Some(line) = lines.iter().find(not_teams) {
+			return Verdict::WrongAnswer(format!("room `{}` is not a row of teams", line));
+		}
+		let placement: Vec<usize> = lines.iter().map(|line| line.len()).collect();
+		let placed = placement.iter().sum::<usize>();
+		if placed != teams {
+			return Verdict::WrongAnswer(format!("expected {} teams, placed {}", teams, placed));
+		}
+		let (fewest, most) = (placement.iter().min().unwrap(), placement.iter().max().unwrap());
+		if most - fewest > 1 {
+			return Verdict::WrongAnswer(format!(
+				"uneven placement: {} teams in one room, {} in another",
+				most, fewest
+			));
+		}
+		Verdict::Accepted
+	}
+}
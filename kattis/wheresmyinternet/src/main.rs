@@ -1,26 +1,28 @@
+#[cfg(feature = "diagnostics")]
+use shared::diagnostics::{self, Phase};
 use std::{
-	cmp::{max, min},
-	collections::{BTreeMap, BTreeSet},
-	fmt::{Debug, Formatter},
-	io::Read
+	fmt::{Debug, Display, Formatter},
+	io::Read,
+	str::FromStr
 };
 
 fn main() {
+	// the panic context of `shared::diagnostics` in local runs, submissions are built without it
+	#[cfg(feature = "diagnostics")]
+	diagnostics::install();
 	// take input from kattis
 	let mut input = String::new();
 	let _result = std::io::stdin()
 		.read_to_string(&mut input)
 		.expect("Could not read from stdin()");
-
-	let output = solve_for_input(input);
-	match &output {
-		Output::Connected => println!("{}", CONNECTED),
-		Output::Missing(vec) => {
-			for id in vec.iter() {
-				println!("{}", id);
-			}
-		},
-	}
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_input(&input);
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Parsing);
+	let problem: Problem = input.parse().expect("Cannot parse problem!");
+	#[cfg(feature = "diagnostics")]
+	diagnostics::set_phase(Phase::Solving);
+	println!("{}", connected_houses(&problem));
 }
 
 const CONNECTED: &str = "Connected";
@@ -31,277 +33,377 @@ enum Output {
 	Missing(Vec<ID>)
 }
 
-type ID = usize;
+impl Display for Output {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Output::Connected => write!(f, "{}", CONNECTED),
+			Output::Missing(ids) => {
+				let lines: Vec<_> = ids.iter().map(|id| id.to_string()).collect();
+				write!(f, "{}", lines.join("\n"))
+			},
+		}
+	}
+}
 
-fn solve_for_input(input: impl AsRef<str>) -> Output {
-	eprintln!(
-		"Trying to solve for the following input:\n{}",
-		input.as_ref()
-	);
-	let mut lines = input.as_ref().lines();
-
-	const N_LOWER_BOUND: usize = 2;
-	const M_LOWER_BOUND: usize = 1;
-	const M_UPPER_BOUND: usize = 200_000;
-
-	let (expected_house_count, completed_connection_count) = {
-		let (first, second) = lines.next().unwrap().split_once(' ').unwrap();
-		(
-			max(first.parse::<usize>().unwrap(), N_LOWER_BOUND), // N
-			min(second.parse::<usize>().unwrap(), M_UPPER_BOUND)  // M
-		)
-	};
+type ID = usize;
 
-	// collect house & connection data
-	let mut house_map: BTreeMap<ID, HouseData> = BTreeMap::from([(1, HouseData {
-		internet_connection: InternetState::ConfirmedConnected,
-		connections:         BTreeSet::new()
-	})]);
-	let mut house_index = N_LOWER_BOUND;
-	let mut connection_index = M_LOWER_BOUND;
-	for line in lines.by_ref() {
-		let ids = line
-			.split(' ')
-			.map(|house_str| house_str.parse::<ID>().expect("Could not parse int!"));
-
-		// count houses
-		for id in ids.clone() {
-			if (N_LOWER_BOUND..=expected_house_count).contains(&house_index)
-				&& !house_map.contains_key(&id)
-			{
-				assert_eq!(house_map.insert(id, HouseData::default()), None);
-				eprintln!("Added house [{}] to set!", id);
-				house_index += 1;
+#[derive(Debug)]
+struct Problem {
+	houses:      usize,        // N, every house is in 1..=N
+	connections: Vec<(ID, ID)>  // the M completed connections
+}
+impl FromStr for Problem {
+	type Err = String;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		// two numbers per line, but any whitespace between them will do
+		let mut numbers = input.trim_start_matches('\u{feff}').split_whitespace().map(|n| {
+			#[cfg(feature = "diagnostics")]
+			diagnostics::point_at(input, n);
+			n.parse::<usize>()
+				.map_err(|e| format!("Bad number `{}`: {}", n, e))
+		});
+		let mut pair = || -> Result<Option<(usize, usize)>, String> {
+			match (numbers.next(), numbers.next()) {
+				(None, _) => Ok(None),
+				(Some(a), Some(b)) => Ok(Some((a?, b?))),
+				(Some(a), None) => Err(format!("Expected two numbers, found only {}", a?))
 			}
+		};
+		let (houses, connection_count) = pair()?.ok_or("No first line!")?;
+		if houses == 0 {
+			return Err("There is no house 1 without houses".to_string());
+		}
+		let mut connections = Vec::new();
+		while let Some(connection) = pair()? {
+			connections.push(connection);
+		}
+		if connections.len() != connection_count {
+			return Err(format!(
+				"Expected {} connections, found {}",
+				connection_count,
+				connections.len()
+			));
 		}
+		if let Some(id) = connections
+			.iter()
+			.flat_map(|&(a, b)| [a, b])
+			.find(|id| !(1..=houses).contains(id))
+		{
+			return Err(format!("House {} is not in 1..={}", id, houses));
+		}
+		Ok(Problem {
+			houses,
+			connections
+		})
+	}
+}
 
-		// count connections
-		if (M_LOWER_BOUND..=completed_connection_count).contains(&connection_index) {
-			assert_eq!(2, ids.clone().count()); // "Connection must be formed between two houses!"
-			let connection_pair = ids.clone().zip(ids.clone().rev());
-			assert_eq!(2, connection_pair.clone().count()); //"Zip must have two iterations!"
-												// connect b to a, and a to b
-			let connection_established = connection_pair
-				.into_iter()
-				.map(|(source, target)| {
-					// connect if not already connected
-					let made_connection = house_map
-						.get_mut(&source)
-						.expect("Map should already have this ID.")
-						.connections
-						.insert(target);
-					if made_connection {
-						eprintln!("Connected {source} to {target}.");
-					}
-					made_connection
-					// evaluate "any" after doing full iter
-				})
-				.reduce(|a, b| a || b)
-				.expect("Connection must be formed between two houses!");
-			if connection_established {
-				connection_index += 1;
+/// The houses that cannot reach house 1, including those without any connection.
+fn connected_houses(problem: &Problem) -> Output {
+	let mut neighbors = vec![Vec::new(); problem.houses];
+	for &(a, b) in problem.connections.iter() {
+		neighbors[a - 1].push(b - 1);
+		neighbors[b - 1].push(a - 1);
+	}
+	let mut connected = vec![false; problem.houses];
+	connected[0] = true;
+	let mut stack = vec![0];
+	while let Some(house) = stack.pop() {
+		for &next in neighbors[house].iter() {
+			if !connected[next] {
+				connected[next] = true;
+				stack.push(next);
 			}
 		}
 	}
-
-	eprintln!("house_map after parsing:{:?}\n", house_map);
-	// assert_eq!(expected_house_count, house_map.len());
-
-	let mut unconnected_house_ids = house_map
-		.clone()
-		.into_keys()
-		.filter(|id| {
-			eprintln!();
-			!has_internet_connection(id, &mut house_map, BTreeSet::new())
-		})
-		// .cloned()
-		.collect::<Vec<_>>();
-
-	if unconnected_house_ids.is_empty() {
+	let missing: Vec<ID> = (0..problem.houses)
+		.filter(|&house| !connected[house])
+		.map(|house| house + 1)
+		.collect();
+	if missing.is_empty() {
 		Output::Connected
 	} else {
-		unconnected_house_ids.sort();
-		Output::Missing(unconnected_house_ids)
+		Output::Missing(missing)
 	}
 }
 
-fn has_internet_connection(
-	this_id: &ID,
-	house_map: &mut BTreeMap<ID, HouseData>,
-	mut seen_ids: BTreeSet<ID>
-) -> bool {
-	let info = house_map
-		.get(this_id)
-		.expect("Info map should always contain this");
-	eprint!("[{}]", this_id);
-	match info.internet_connection {
-		InternetState::Unknown => {
-			assert!(seen_ids.insert(*this_id));
-			let connections = house_map.get(this_id).unwrap().connections.clone(); // connections remain unchanged
-			connections.iter().any(|other_id| {
-				!seen_ids.contains(other_id)
-					&& ({
-						eprint!(" -> ");
-						let found_internet =
-							has_internet_connection(other_id, house_map, seen_ids.clone());
-						if found_internet {
-							eprint!(" ...which gives internet to [{}]", this_id);
-							let info_mut = house_map.get_mut(this_id).unwrap();
-							info_mut.internet_connection = InternetState::ConfirmedConnected;
-						}
-						found_internet
-					})
-			}) || ({
-				// this path is always false but we want do extra work
-				house_map.get_mut(this_id).unwrap().internet_connection =
-					InternetState::ConfirmedDisconnected;
-				!connections.is_empty()
-					&& ({
-						eprintln!(); // print space after non-empty
-						false
-					})
-			})
-		},
-		InternetState::ConfirmedConnected => {
-			eprint!(" ...already has internet!");
-			true
-		},
-		InternetState::ConfirmedDisconnected => false
-	}
-}
+// the first solutions, which search from every house or spread the connection from house 1 and
+// recurse once per house along the way. they need a big stack and print every step, so they are
+// only compared against `connected_houses` in the tests instead of being submitted
+#[cfg(test)]
+mod recursive {
+	use std::{
+		collections::{btree_map::Entry, BTreeMap, BTreeSet},
+		fmt::{Debug, Formatter}
+	};
 
-#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
-struct HouseData {
-	internet_connection: InternetState,
-	connections:         BTreeSet<ID>
-}
-impl Debug for HouseData {
-	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{:?}{:?}", self.internet_connection, self.connections)
+	use crate::{connected_houses, Output, Problem, ID};
+
+	pub type Solve = fn(&str) -> String;
+
+	// every solver, `connected_houses` first
+	pub const SOLVERS: [(&str, Solve); 3] = [
+		// follows the connections from house 1, without recursion
+		("reach", |input| {
+			let problem: Problem = input.parse().expect("Cannot parse problem!");
+			connected_houses(&problem).to_string()
+		}),
+		// searches for a connected house from every house
+		("search", |input| solve_for_input(input).to_string()),
+		// spreads the connection from house 1
+		("flood", |input| wheresmyinternet(input.to_string()))
+	];
+
+	pub fn solve_for_input(input: impl AsRef<str>) -> Output {
+		eprintln!(
+			"Trying to solve for the following input:\n{}",
+			input.as_ref()
+		);
+		let problem: Problem = input.as_ref().parse().expect("Cannot parse problem!");
+		let house_map = search_houses(&problem);
+
+		let unconnected_house_ids = house_map
+			.iter()
+			.filter(|(_id, data)| data.internet_connection != InternetState::ConfirmedConnected)
+			.map(|(id, _data)| *id)
+			.collect::<Vec<_>>();
+
+		if unconnected_house_ids.is_empty() {
+			Output::Connected
+		} else {
+			Output::Missing(unconnected_house_ids)
+		}
 	}
-}
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
-enum InternetState {
-	#[default]
-	Unknown,
-	ConfirmedConnected,
-	ConfirmedDisconnected
-}
 
-pub fn wheresmyinternet(input: String) -> String {
-	let mut lines = input.lines();
-
-	let _no_houses = lines
-		.next()
-		.unwrap()
-		.split_once(' ')
-		.unwrap()
-		.0
-		.parse::<usize>()
-		.unwrap();
-
-	let rest = lines
-		.map(|s| s.split_once(' ').unwrap())
-		.map(|(a, b)| (a.parse::<usize>().unwrap(), b.parse::<usize>().unwrap()));
-
-	let mut house_map: BTreeMap<ID, HouseData> = BTreeMap::from([(1, HouseData {
-		internet_connection: InternetState::ConfirmedConnected,
-		..Default::default()
-	})]);
-
-	fn connect(map: &mut BTreeMap<ID, HouseData>, source: ID, target: ID) {
-		map.entry(source)
-			.and_modify(|source_data| {
-				source_data.connections.insert(target);
-			})
-			.or_insert({
-				HouseData {
-					connections: BTreeSet::from([target]),
-					..Default::default()
+	/// Every house with its connections, and whether it has internet once searched from.
+	pub fn search_houses(problem: &Problem) -> BTreeMap<ID, HouseData> {
+		// collect house & connection data
+		let mut house_map: BTreeMap<ID, HouseData> = BTreeMap::from([(1, HouseData {
+			internet_connection: InternetState::ConfirmedConnected,
+			connections:         BTreeSet::new()
+		})]);
+		// houses without any connection have no internet either
+		for id in 2..=problem.houses {
+			house_map.insert(id, HouseData::default());
+		}
+		for &(a, b) in problem.connections.iter() {
+			for id in [a, b] {
+				if let Entry::Vacant(entry) = house_map.entry(id) {
+					entry.insert(HouseData::default());
+					eprintln!("Added house [{}] to set!", id);
 				}
-			});
-	}
+			}
+			// connect b to a, and a to b
+			for (source, target) in [(a, b), (b, a)] {
+				let made_connection = house_map
+					.get_mut(&source)
+					.expect("Map should already have this ID.")
+					.connections
+					.insert(target);
+				if made_connection {
+					eprintln!("Connected {source} to {target}.");
+				}
+			}
+		}
 
-	for (a, b) in rest {
-		connect(&mut house_map, a, b);
-		connect(&mut house_map, b, a);
-	}
+		eprintln!("house_map after parsing:{:?}\n", house_map);
 
-	eprintln!("Connections *before*:\n{:?}", house_map);
-
-	// let mut flags = vec![false; no_houses];
-
-	// fn descent(map: &BTreeMap<ID, HouseData>, id: ID, max: usize, flags: &mut
-	// Vec<bool>) {     if max == 0 || flags[id - 1] {
-	//         return;
-	//     }
-	//     flags[id - 1] = true;
-	//     let binding = HouseData::default();
-	//     let connections = &map.get(&id).unwrap_or(&binding).connections; // Use
-	// unwrap_or to handle None     for &neighbor in connections {
-	//         if !flags[neighbor - 1] {
-	//             descent(map, neighbor, max - 1, flags);
-	//         }
-	//     }
-	// }
-	// descent(&map, 1, no_houses, &mut flags);
-
-	fn try_connect_from(
-		this_id: ID,
-		map: &mut BTreeMap<ID, HouseData>,
-		traveled_ids: &mut BTreeSet<ID>
-	) {
-		assert!(traveled_ids.insert(this_id));
-		let state = map.get(&this_id).unwrap().internet_connection.clone();
-		for other_id in map.get(&this_id).unwrap().connections.clone().iter() {
-			if !traveled_ids.contains(other_id) {
-				if state == InternetState::ConfirmedConnected {
-					map.get_mut(other_id).unwrap().internet_connection =
-						InternetState::ConfirmedConnected;
-				}
-				try_connect_from(*other_id, map, traveled_ids);
-				if state != InternetState::ConfirmedConnected
-					&& map.get(other_id).unwrap().internet_connection
-						== InternetState::ConfirmedConnected
-				{
-					map.get_mut(&this_id).unwrap().internet_connection =
-						InternetState::ConfirmedConnected;
+		let ids = house_map.keys().copied().collect::<Vec<_>>();
+		for id in ids {
+			eprintln!();
+			let mut seen_ids = BTreeSet::new();
+			if !has_internet_connection(&id, &mut house_map, &mut seen_ids) {
+				// the search saw everything reachable from here, none of it has internet
+				for seen_id in seen_ids {
+					house_map.get_mut(&seen_id).unwrap().internet_connection =
+						InternetState::ConfirmedDisconnected;
 				}
 			}
 		}
-		if state == InternetState::Unknown {
-			map.get_mut(&this_id).unwrap().internet_connection =
-				InternetState::ConfirmedDisconnected
+		house_map
+	}
+
+	fn has_internet_connection(
+		this_id: &ID,
+		house_map: &mut BTreeMap<ID, HouseData>,
+		seen_ids: &mut BTreeSet<ID>
+	) -> bool {
+		let info = house_map
+			.get(this_id)
+			.expect("Info map should always contain this");
+		eprint!("[{}]", this_id);
+		match info.internet_connection {
+			InternetState::Unknown => {
+				assert!(seen_ids.insert(*this_id));
+				// connections remain unchanged
+				let connections = house_map.get(this_id).unwrap().connections.clone();
+				// a house without internet is only known to be disconnected once the whole search
+				// is done, as the houses seen before might still lead to it
+				connections.iter().any(|other_id| {
+					!seen_ids.contains(other_id)
+						&& ({
+							eprint!(" -> ");
+							let found_internet =
+								has_internet_connection(other_id, house_map, seen_ids);
+							if found_internet {
+								eprint!(" ...which gives internet to [{}]", this_id);
+								let info_mut = house_map.get_mut(this_id).unwrap();
+								info_mut.internet_connection = InternetState::ConfirmedConnected;
+							}
+							found_internet
+						})
+				})
+			},
+			InternetState::ConfirmedConnected => {
+				eprint!(" ...already has internet!");
+				true
+			},
+			InternetState::ConfirmedDisconnected => false
+		}
+	}
+
+	#[derive(Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+	pub struct HouseData {
+		pub internet_connection: InternetState,
+		pub connections:         BTreeSet<ID>
+	}
+	impl Debug for HouseData {
+		fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+			write!(f, "{:?}{:?}", self.internet_connection, self.connections)
 		}
 	}
-	try_connect_from(1, &mut house_map, &mut BTreeSet::new());
+	#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+	pub enum InternetState {
+		#[default]
+		Unknown,
+		ConfirmedConnected,
+		ConfirmedDisconnected
+	}
 
-	eprintln!("Connections *after*:\n{:?}", house_map);
+	pub fn wheresmyinternet(input: String) -> String {
+		let problem: Problem = input.parse().expect("Cannot parse problem!");
 
-	let unvisited = house_map
-		.iter()
-		.filter(|(_id, data)| data.internet_connection != InternetState::ConfirmedConnected)
-		.map(|(id, _data)| id)
-		.collect::<Vec<_>>();
-	if unvisited.is_empty() {
-		"Connected".to_string()
-	} else {
-		unvisited
+		let mut house_map: BTreeMap<ID, HouseData> = BTreeMap::from([(1, HouseData {
+			internet_connection: InternetState::ConfirmedConnected,
+			..Default::default()
+		})]);
+
+		fn connect(map: &mut BTreeMap<ID, HouseData>, source: ID, target: ID) {
+			map.entry(source)
+				.and_modify(|source_data| {
+					source_data.connections.insert(target);
+				})
+				.or_insert({
+					HouseData {
+						connections: BTreeSet::from([target]),
+						..Default::default()
+					}
+				});
+		}
+
+		for id in 2..=problem.houses {
+			house_map.insert(id, HouseData::default());
+		}
+		for &(a, b) in problem.connections.iter() {
+			connect(&mut house_map, a, b);
+			connect(&mut house_map, b, a);
+		}
+
+		eprintln!("Connections *before*:\n{:?}", house_map);
+
+		// let mut flags = vec![false; no_houses];
+
+		// fn descent(map: &BTreeMap<ID, HouseData>, id: ID, max: usize, flags: &mut
+		// Vec<bool>) {     if max == 0 || flags[id - 1] {
+		//         return;
+		//     }
+		//     flags[id - 1] = true;
+		//     let binding = HouseData::default();
+		//     let connections = &map.get(&id).unwrap_or(&binding).connections; // Use
+		// unwrap_or to handle None     for &neighbor in connections {
+		//         if !flags[neighbor - 1] {
+		//             descent(map, neighbor, max - 1, flags);
+		//         }
+		//     }
+		// }
+		// descent(&map, 1, no_houses, &mut flags);
+
+		fn try_connect_from(
+			this_id: ID,
+			map: &mut BTreeMap<ID, HouseData>,
+			traveled_ids: &mut BTreeSet<ID>
+		) {
+			assert!(traveled_ids.insert(this_id));
+			let state = map.get(&this_id).unwrap().internet_connection.clone();
+			for other_id in map.get(&this_id).unwrap().connections.clone().iter() {
+				if !traveled_ids.contains(other_id) {
+					if state == InternetState::ConfirmedConnected {
+						map.get_mut(other_id).unwrap().internet_connection =
+							InternetState::ConfirmedConnected;
+					}
+					try_connect_from(*other_id, map, traveled_ids);
+					if state != InternetState::ConfirmedConnected
+						&& map.get(other_id).unwrap().internet_connection
+							== InternetState::ConfirmedConnected
+					{
+						map.get_mut(&this_id).unwrap().internet_connection =
+							InternetState::ConfirmedConnected;
+					}
+				}
+			}
+			if state == InternetState::Unknown {
+				map.get_mut(&this_id).unwrap().internet_connection =
+					InternetState::ConfirmedDisconnected
+			}
+		}
+		try_connect_from(1, &mut house_map, &mut BTreeSet::new());
+
+		eprintln!("Connections *after*:\n{:?}", house_map);
+
+		let unvisited = house_map
 			.iter()
-			.map(|id| format!("{}\n", id))
-			.collect::<String>()
-			.trim()
-			.to_string()
+			.filter(|(_id, data)| data.internet_connection != InternetState::ConfirmedConnected)
+			.map(|(id, _data)| id)
+			.collect::<Vec<_>>();
+		if unvisited.is_empty() {
+			"Connected".to_string()
+		} else {
+			unvisited
+				.iter()
+				.map(|id| format!("{}\n", id))
+				.collect::<String>()
+				.trim()
+				.to_string()
+		}
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use std::str::FromStr;
+	use std::{
+		collections::{BTreeSet, HashSet},
+		str::FromStr
+	};
 
 	use seq_macro::seq;
+	#[cfg(feature = "diagnostics")]
+	use shared::diagnostics;
+	use shared::{
+		dot::{Dot, Kind, NodeState},
+		driver::{with_stack, Settings, BIG_STACK},
+		graph::{Direction, Graph},
+		normalize::{assert_same_when_messy, Whitespace},
+		property,
+		random::Rng,
+		solvers::{differential, Solver}
+	};
 
-	use crate::{solve_for_input, wheresmyinternet, Output, CONNECTED};
+	use crate::{
+		connected_houses,
+		recursive::{search_houses, solve_for_input, wheresmyinternet, InternetState, SOLVERS},
+		Output, Problem, CONNECTED, ID
+	};
 
 	impl FromStr for Output {
 		type Err = String;
@@ -320,6 +422,16 @@ mod tests {
 		}
 	}
 
+	impl NodeState for InternetState {
+		fn color(&self) -> &'static str {
+			match self {
+				InternetState::Unknown => "lightgray",
+				InternetState::ConfirmedConnected => "palegreen",
+				InternetState::ConfirmedDisconnected => "lightsalmon"
+			}
+		}
+	}
+
 	seq!(N in 1..=3 {
 		const INPUT_STR_~N: &str = include_str!(stringify!(in_~N.txt));
 		const OUTPUT_STR_~N: &str = include_str!(stringify!(out_~N.txt));
@@ -340,6 +452,83 @@ mod tests {
 			OUTPUT_STR_~N.parse::<Output>().unwrap();
 		}
 
+		// `sample_~N.dot` in `DOT_DIRECTORY`, houses colored by whether they have internet
+		#[test]
+		fn house_graph_~N() {
+			let problem: Problem = INPUT_STR_~N.parse().unwrap();
+			let house_map = search_houses(&problem);
+			let connections = house_map.iter().map(|(id, data)| (id, &data.connections));
+			let mut dot = Dot::from_adjacency("houses", Kind::Undirected, connections);
+			for (id, data) in house_map.iter() {
+				dot.state(id, &data.internet_connection);
+			}
+			dot.write(concat!("sample_", N)).expect("Could not write the graph!");
+			// every house colored, every cable drawn once
+			let rendered = dot.to_string();
+			assert_eq!(rendered.matches("fillcolor").count(), problem.houses);
+			assert_eq!(rendered.matches(" -- ").count(), problem.connections.len());
+			let connected = OUTPUT_STR_~N.parse::<Output>().unwrap() == Output::Connected;
+			assert_eq!(connected, !rendered.contains("lightsalmon"));
+		}
+
+		// the same houses and connections in the shared graph type
+		#[test]
+		fn network_graph_~N() {
+			let problem: Problem = INPUT_STR_~N.parse().unwrap();
+			let graph = Graph::from_one_based(
+				problem.houses,
+				Direction::Undirected,
+				problem.connections.iter().copied()
+			);
+			for (id, data) in search_houses(&problem) {
+				let neighbors: BTreeSet<ID> =
+					graph.neighbors(id - 1).iter().map(|neighbor| neighbor + 1).collect();
+				assert_eq!(neighbors, data.connections, "house {}", id);
+			}
+		}
+
+		// the houses that cannot reach house 1, straight from the shared graph
+		#[test]
+		fn reachable_houses_~N() {
+			let problem: Problem = INPUT_STR_~N.parse().unwrap();
+			let graph = Graph::from_one_based(
+				problem.houses,
+				Direction::Undirected,
+				problem.connections.iter().copied()
+			);
+			let missing: Vec<ID> = graph
+				.reachable_from(0)
+				.into_iter()
+				.enumerate()
+				.filter(|&(_, reached)| !reached)
+				.map(|(index, _)| index + 1)
+				.collect();
+			let output = match missing.is_empty() {
+				true => Output::Connected,
+				false => Output::Missing(missing)
+			};
+			assert_eq!(output, OUTPUT_STR_~N.parse().unwrap());
+		}
+
+		// the cables that cut houses off from house 1 when they fail
+		#[test]
+		fn critical_cables_~N() {
+			let problem: Problem = INPUT_STR_~N.parse().unwrap();
+			let houses = |connections: &[(ID, ID)]| {
+				Graph::from_one_based(problem.houses, Direction::Undirected, connections.to_vec())
+			};
+			let graph = houses(&problem.connections);
+			let cuts = graph.cuts();
+			let connected = graph.reachable_from(0);
+			for (cable, &(a, b)) in problem.connections.iter().enumerate() {
+				let mut rest = problem.connections.clone();
+				rest.remove(cable);
+				let still_connected = houses(&rest).reachable_from(0);
+				let critical = cuts.is_bridge(cable) && connected[a - 1];
+				assert_eq!(connected != still_connected, critical, "cable {} - {}", a, b);
+			}
+		}
+
 		#[test]
 		fn solve_test_~N() {
 			let solved_output = solve_for_input(INPUT_STR_~N);
@@ -351,4 +540,162 @@ mod tests {
 			);
 		}
 	});
-}
\ No newline at end of file
+
+	// houses that are in no connection cannot reach house 1 either
+	#[test]
+	fn isolated_houses() {
+		for (name, solve) in SOLVERS {
+			assert_eq!(solve("3 1\n1 2\n"), "3", "solver {}", name);
+			assert_eq!(solve("4 1\n2 3\n"), "2\n3\n4", "solver {}", name);
+			assert_eq!(solve("1 0\n"), CONNECTED, "solver {}", name);
+		}
+	}
+
+	fn reachable_answer(houses: usize, connections: &[(usize, usize)]) -> Output {
+		let graph = Graph::from_edges(houses, Direction::Undirected, connections.iter().copied());
+		let missing: Vec<ID> = graph
+			.reachable_from(0)
+			.into_iter()
+			.enumerate()
+			.filter(|&(_, reached)| !reached)
+			.map(|(index, _)| index + 1)
+			.collect();
+		match missing.is_empty() {
+			true => Output::Connected,
+			false => Output::Missing(missing)
+		}
+	}
+
+	fn input(houses: usize, connections: &[(usize, usize)]) -> String {
+		let lines: Vec<_> = connections
+			.iter()
+			.map(|(a, b)| format!("{} {}", a + 1, b + 1))
+			.collect();
+		format!("{} {}\n{}\n", houses, connections.len(), lines.join("\n"))
+	}
+
+	// what the problem promises: at least one house, no connection to itself or twice
+	fn valid_town(houses: usize, connections: &[(usize, usize)]) -> bool {
+		let mut seen = HashSet::new();
+		houses >= 1
+			&& connections.iter().all(|&(a, b)| {
+				a < houses && b < houses && a != b && seen.insert((a.min(b), a.max(b)))
+			})
+	}
+
+	// the solvers agreeing with each other is not enough, they all used to skip lonely houses
+	#[test]
+	fn solvers_match_reachability() {
+		property::check_where(
+			|rng| {
+				let houses = rng.range(1..=20);
+				let connections = rng.range(0..=houses * (houses - 1) / 2);
+				(houses, rng.graph(houses, connections))
+			},
+			|(houses, connections)| valid_town(*houses, connections),
+			|(houses, connections)| {
+				let expected = reachable_answer(*houses, connections).to_string();
+				for (name, solve) in SOLVERS {
+					let output = solve(&input(*houses, connections));
+					property::ensure(output == expected, || {
+						format!("solver {} says {:?} instead of {:?}", name, output, expected)
+					})?;
+				}
+				Ok(())
+			}
+		);
+	}
+
+	#[test]
+	fn big_random_town() {
+		let mut rng = Rng::new(property::Config::default().seed);
+		let houses = 200_000;
+		let connections: Vec<_> = (0..houses)
+			.map(|_| (rng.range(0..=houses - 1), rng.range(0..=houses - 1)))
+			.collect();
+		let problem: Problem = input(houses, &connections).parse().unwrap();
+		assert_eq!(connected_houses(&problem), reachable_answer(houses, &connections));
+	}
+
+	// two cables between the same houses back each other up, a cable to itself never matters
+	#[test]
+	fn doubled_cables() {
+		let problem: Problem = "4 5\n1 2\n2 1\n2 3\n3 3\n3 4".parse().unwrap();
+		let graph = Graph::from_one_based(
+			problem.houses,
+			Direction::Undirected,
+			problem.connections.iter().copied()
+		);
+		let cuts = graph.cuts();
+		assert_eq!(cuts.bridges(), &[2, 4]);
+		assert_eq!(cuts.articulation_points(), &[1, 2]);
+		assert_eq!(cuts.members(), vec![vec![0, 1], vec![2], vec![3]]);
+	}
+
+	#[test]
+	fn long_chain() {
+		// 1 - 2 - ... - 200000
+		let houses = 200_000;
+		let input = std::iter::once(format!("{} {}", houses, houses - 1))
+			.chain((1..houses).map(|id| format!("{} {}", id, id + 1)))
+			.collect::<Vec<_>>()
+			.join("\n");
+		// one recursion per house, far too deep for the 2 MiB test threads
+		let output = with_stack(Some(BIG_STACK), move || wheresmyinternet(input));
+		assert_eq!(output, CONNECTED);
+	}
+
+	#[test]
+	fn parser_never_panics() {
+		shared::fuzz::check_parser::<Problem>(&[INPUT_STR_1, INPUT_STR_2, INPUT_STR_3]);
+	}
+
+	// `cargo test solvers_agree -- --nocapture` shows how fast every solver was
+	#[test]
+	fn solvers_agree() {
+		let solvers = SOLVERS.map(|(name, solve)| Solver { name, solve });
+		let seed = property::Config::default().seed;
+		let mut rng = Rng::new(seed);
+		let mut inputs = vec![
+			("sample 1".to_string(), INPUT_STR_1.to_string()),
+			("sample 2".to_string(), INPUT_STR_2.to_string()),
+			("sample 3".to_string(), INPUT_STR_3.to_string()),
+		];
+		for index in 1..=200 {
+			let houses = rng.range(2..=40);
+			let connections = rng.range(1..=houses * (houses - 1) / 2);
+			let lines: Vec<_> = rng
+				.graph(houses, connections)
+				.into_iter()
+				.map(|(a, b)| format!("{} {}", a + 1, b + 1))
+				.collect();
+			let input = format!("{} {}\n{}\n", houses, connections, lines.join("\n"));
+			inputs.push((format!("generated #{} (seed {})", index, seed), input));
+		}
+		let report = differential(&solvers, &inputs, Settings {
+			stack_size: Some(BIG_STACK),
+			..Default::default()
+		});
+		println!("{}", report);
+		report.assert_agreement();
+	}
+
+	#[test]
+	fn whitespace_does_not_matter() {
+		for input in [INPUT_STR_1, INPUT_STR_2, INPUT_STR_3] {
+			assert_same_when_messy(input, Whitespace::Insignificant, |input| {
+				input.parse::<Problem>().map(|problem| connected_houses(&problem))
+			});
+		}
+	}
+
+	#[cfg(feature = "diagnostics")]
+	#[test]
+	fn parse_error_points_at_number() {
+		let input = "3 2\n1 2\n2 x\n";
+		diagnostics::install();
+		diagnostics::set_input(input);
+		assert!(input.parse::<Problem>().is_err());
+		assert_eq!(diagnostics::report().unwrap(), "line 3, column 3:\n    3 | 2 x\n      |   ^");
+	}
+}
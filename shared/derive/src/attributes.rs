@@ -0,0 +1,86 @@
+use syn::{Attribute, Expr, GenericArgument, LitStr, PathArguments, Type};
+
+/// Everything that can be put in `#[kattis(...)]` on a field (or an enum variant).
+#[derive(Default)]
+pub struct FieldLayout {
+	pub newline:   bool,
+	pub line:      bool,
+	pub per_line:  bool,
+	pub rest:      bool,
+	pub count:     Option<Expr>,
+	pub grid:      Option<Grid>,
+	pub precision: Option<Expr>,  // output only
+	pub literal:   Option<LitStr>  // output only, on unit variants
+}
+
+pub struct Grid {
+	pub rows:    Expr,
+	pub columns: Option<Expr>
+}
+
+impl FieldLayout {
+	pub fn parse(attributes: &[Attribute]) -> syn::Result<Self> {
+		let mut layout = FieldLayout::default();
+		for attribute in attributes.iter().filter(|a| a.path().is_ident("kattis")) {
+			attribute.parse_nested_meta(|meta| {
+				if meta.path.is_ident("newline") {
+					layout.newline = true;
+				} else if meta.path.is_ident("line") {
+					layout.line = true;
+				} else if meta.path.is_ident("per_line") {
+					layout.per_line = true;
+				} else if meta.path.is_ident("rest") {
+					layout.rest = true;
+				} else if meta.path.is_ident("count") {
+					layout.count = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("precision") {
+					layout.precision = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("literal") {
+					layout.literal = Some(meta.value()?.parse()?);
+				} else if meta.path.is_ident("grid") {
+					let mut rows = None;
+					let mut columns = None;
+					meta.parse_nested_meta(|meta| {
+						if meta.path.is_ident("rows") {
+							rows = Some(meta.value()?.parse()?);
+						} else if meta.path.is_ident("cols") {
+							columns = Some(meta.value()?.parse()?);
+						} else {
+							return Err(meta.error("expected `rows` or `cols`"));
+						}
+						Ok(())
+					})?;
+					let rows = rows.ok_or_else(|| meta.error("a grid needs `rows = ...`"))?;
+					layout.grid = Some(Grid { rows, columns });
+				} else {
+					return Err(meta.error("unknown kattis layout attribute"));
+				}
+				Ok(())
+			})?;
+		}
+		Ok(layout)
+	}
+
+	/// Attributes that only make sense on a `Vec`.
+	pub fn is_sequence(&self) -> bool {
+		self.per_line || self.rest || self.count.is_some() || self.grid.is_some()
+	}
+}
+
+/// `T` if the type is written as `Vec<T>`.
+pub fn vec_element(ty: &Type) -> Option<&Type> {
+	let Type::Path(path) = ty else {
+		return None;
+	};
+	let segment = path.path.segments.last()?;
+	if segment.ident != "Vec" {
+		return None;
+	}
+	let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+		return None;
+	};
+	match arguments.args.first()? {
+		GenericArgument::Type(element) => Some(element),
+		_ => None
+	}
+}
@@ -0,0 +1,132 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Field, Fields, Ident, Type};
+
+use crate::attributes::{vec_element, FieldLayout};
+
+pub fn derive(item: &DeriveInput) -> syn::Result<TokenStream> {
+	let Data::Struct(data) = &item.data else {
+		return Err(Error::new_spanned(
+			item,
+			"KattisInput can only be derived for structs"
+		));
+	};
+	let Fields::Named(fields) = &data.fields else {
+		return Err(Error::new_spanned(
+			item,
+			"KattisInput needs a struct with named fields"
+		));
+	};
+
+	let reads = fields
+		.named
+		.iter()
+		.map(|field| {
+			let layout = FieldLayout::parse(&field.attrs)?;
+			if layout.precision.is_some() || layout.literal.is_some() {
+				return Err(Error::new_spanned(
+					field,
+					"`precision` and `literal` are only used by KattisOutput"
+				));
+			}
+			read_field(field, field.ident.as_ref().unwrap(), &layout)
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
+	let names = fields.named.iter().map(|field| &field.ident);
+
+	let name = &item.ident;
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::shared::input::KattisInput for #name #type_generics #where_clause {
+			fn read(
+				__scanner: &mut ::shared::scanner::Scanner<'_>
+			) -> ::core::result::Result<Self, ::shared::scanner::ParseError> {
+				#(#reads)*
+				::core::result::Result::Ok(Self { #(#names),* })
+			}
+		}
+
+		impl #impl_generics ::core::str::FromStr for #name #type_generics #where_clause {
+			type Err = ::shared::scanner::ParseError;
+
+			fn from_str(input: &str) -> ::core::result::Result<Self, Self::Err> {
+				::shared::input::parse(input)
+			}
+		}
+	})
+}
+
+/// `let binding = ...;` reading the field according to its layout.
+pub fn read_field(field: &Field, binding: &Ident, layout: &FieldLayout) -> syn::Result<TokenStream> {
+	// rows of a grid or a list per line start on a new line already, so this would skip two
+	if layout.newline && (layout.grid.is_some() || layout.per_line) {
+		return Err(Error::new_spanned(
+			field,
+			"`newline` is implied by `grid` and `per_line`, leave it out"
+		));
+	}
+	let newline = layout.newline.then(|| quote!(__scanner.end_line()?;));
+
+	let value = if let Some(grid) = &layout.grid {
+		let rows = &grid.rows;
+		let columns = match &grid.columns {
+			Some(columns) => quote! {
+				::core::option::Option::Some(::shared::input::count(__scanner, #columns)?)
+			},
+			None => quote!(::core::option::Option::None)
+		};
+		quote! {{
+			let __rows = ::shared::input::count(__scanner, #rows)?;
+			let __columns = #columns;
+			::shared::input::read_grid(__scanner, __rows, __columns)?
+		}}
+	} else if let Some(element) = vec_element(&field.ty) {
+		let read_element = read_single(element, layout.line);
+		let per_line = layout.per_line;
+		match (&layout.count, layout.rest) {
+			(Some(_), true) => {
+				return Err(Error::new_spanned(
+					field,
+					"`count` and `rest` can't be used together"
+				));
+			},
+			(Some(count), false) => quote! {{
+				let __count = ::shared::input::count(__scanner, #count)?;
+				::shared::input::read_counted(__scanner, __count, #per_line, |__scanner| #read_element)?
+			}},
+			(None, true) => quote! {
+				::shared::input::read_until_end(__scanner, #per_line, |__scanner| #read_element)?
+			},
+			(None, false) if per_line || layout.line => {
+				return Err(Error::new_spanned(
+					field,
+					"`per_line` and `line` need either `count = ...` or `rest`"
+				));
+			},
+			(None, false) => quote! {
+				::shared::input::read_until_line_end(__scanner, |__scanner| #read_element)?
+			}
+		}
+	} else if layout.is_sequence() {
+		return Err(Error::new_spanned(
+			&field.ty,
+			"this layout attribute needs a `Vec` field"
+		));
+	} else {
+		let read = read_single(&field.ty, layout.line);
+		quote!(#read?)
+	};
+
+	Ok(quote! {
+		#newline
+		let #binding = #value;
+	})
+}
+
+fn read_single(ty: &Type, line: bool) -> TokenStream {
+	if line {
+		quote!(::shared::input::read_line::<#ty>(__scanner))
+	} else {
+		quote!(<#ty as ::shared::input::KattisInput>::read(__scanner))
+	}
+}
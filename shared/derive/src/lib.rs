@@ -0,0 +1,24 @@
+//! Derive macros for the `shared` crate, see its docs for the layout attributes.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attributes;
+mod input;
+mod output;
+
+#[proc_macro_derive(KattisInput, attributes(kattis))]
+pub fn derive_kattis_input(item: TokenStream) -> TokenStream {
+	let item = parse_macro_input!(item as DeriveInput);
+	input::derive(&item)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+#[proc_macro_derive(KattisOutput, attributes(kattis))]
+pub fn derive_kattis_output(item: TokenStream) -> TokenStream {
+	let item = parse_macro_input!(item as DeriveInput);
+	output::derive(&item)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
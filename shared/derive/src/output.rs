@@ -0,0 +1,230 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Ident};
+
+use crate::{
+	attributes::{vec_element, FieldLayout},
+	input::read_field
+};
+
+pub fn derive(item: &DeriveInput) -> syn::Result<TokenStream> {
+	let (display, parse) = match &item.data {
+		Data::Struct(data) => {
+			let (pattern, writes, reads) = fields_of(&data.fields, quote!(Self))?;
+			let display = quote! {
+				let #pattern = self;
+				#writes
+				::core::result::Result::Ok(())
+			};
+			let parse = quote! {
+				#reads
+				::core::result::Result::Ok(#pattern)
+			};
+			(display, parse)
+		},
+		Data::Enum(data) => {
+			let mut arms = Vec::new();
+			let mut literal_attempts = Vec::new();
+			let mut field_attempts = Vec::new();
+			let mut expected = Vec::new();
+			for variant in data.variants.iter() {
+				let name = &variant.ident;
+				let layout = FieldLayout::parse(&variant.attrs)?;
+				match (&variant.fields, &layout.literal) {
+					(Fields::Unit, Some(literal)) => {
+						arms.push(quote! {
+							Self::#name => __f.write_str(#literal)
+						});
+						literal_attempts.push(quote! {
+							if ::shared::output::attempt(__scanner, |__scanner| {
+								::shared::output::literal(__scanner, #literal)
+							})
+							.is_some()
+							{
+								return ::core::result::Result::Ok(Self::#name);
+							}
+						});
+						expected.push(format!("`{}`", literal.value()));
+					},
+					(Fields::Unit, None) => {
+						return Err(Error::new_spanned(
+							variant,
+							"unit variants need `#[kattis(literal = \"...\")]`"
+						));
+					},
+					(_, Some(_)) => {
+						return Err(Error::new_spanned(
+							variant,
+							"only unit variants can be written as a literal"
+						));
+					},
+					(fields, None) => {
+						let (pattern, writes, reads) = fields_of(fields, quote!(Self::#name))?;
+						arms.push(quote! {
+							#pattern => {
+								#writes
+								::core::result::Result::Ok(())
+							}
+						});
+						field_attempts.push(quote! {
+							if let ::core::option::Option::Some(__value) =
+								::shared::output::attempt(__scanner, |__scanner| {
+									#reads
+									::core::result::Result::Ok(#pattern)
+								}) {
+								return ::core::result::Result::Ok(__value);
+							}
+						});
+						expected.push(name.to_string());
+					}
+				}
+			}
+			let expected = format!("expected one of {}", expected.join(", "));
+			let display = quote! {
+				match self {
+					#(#arms),*
+				}
+			};
+			// literals first, so "-1" is not read as a number by another variant
+			let parse = quote! {
+				#(#literal_attempts)*
+				#(#field_attempts)*
+				::core::result::Result::Err(__scanner.error(#expected))
+			};
+			(display, parse)
+		},
+		Data::Union(_) => {
+			return Err(Error::new_spanned(
+				item,
+				"KattisOutput can't be derived for unions"
+			));
+		}
+	};
+
+	let name = &item.ident;
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::core::fmt::Display for #name #type_generics #where_clause {
+			// whether the last field wrote anything is not always asked
+			#[allow(unused_assignments, unused_mut, unused_variables)]
+			fn fmt(&self, __f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+				#display
+			}
+		}
+
+		// only needed to compare against the sample answers
+		#[cfg(test)]
+		impl #impl_generics ::shared::input::KattisInput for #name #type_generics #where_clause {
+			fn read(
+				__scanner: &mut ::shared::scanner::Scanner<'_>
+			) -> ::core::result::Result<Self, ::shared::scanner::ParseError> {
+				#parse
+			}
+		}
+
+		#[cfg(test)]
+		impl #impl_generics ::core::str::FromStr for #name #type_generics #where_clause {
+			type Err = ::shared::scanner::ParseError;
+
+			fn from_str(output: &str) -> ::core::result::Result<Self, Self::Err> {
+				::shared::input::parse(output)
+			}
+		}
+	})
+}
+
+/// The destructuring pattern, the statements writing every field,
+/// and the statements reading them back into bindings named like the pattern.
+fn fields_of(
+	fields: &Fields,
+	path: TokenStream
+) -> syn::Result<(TokenStream, TokenStream, TokenStream)> {
+	let bindings: Vec<Ident> = fields
+		.iter()
+		.enumerate()
+		.map(|(index, field)| {
+			field
+				.ident
+				.clone()
+				.unwrap_or_else(|| format_ident!("__field{}", index))
+		})
+		.collect();
+	let pattern = match fields {
+		Fields::Named(_) => quote!(#path { #(#bindings),* }),
+		Fields::Unnamed(_) => quote!(#path(#(#bindings),*)),
+		Fields::Unit => quote!(#path)
+	};
+
+	let mut writes = Vec::new();
+	let mut reads = Vec::new();
+	for (index, (field, binding)) in fields.iter().zip(bindings.iter()).enumerate() {
+		let mut layout = FieldLayout::parse(&field.attrs)?;
+		if layout.literal.is_some() || layout.grid.is_some() || layout.count.is_some() {
+			return Err(Error::new_spanned(
+				field,
+				"KattisOutput fields only take `newline`, `per_line` and `precision`"
+			));
+		}
+
+		// a space only goes between two values, so an empty list leaves no stray space behind
+		if index > 0 && layout.newline {
+			writes.push(quote! {
+				__f.write_str("\n")?;
+				__line_started = false;
+			});
+		}
+		let write_one = |value: TokenStream| match &layout.precision {
+			Some(precision) => quote!(::core::write!(__f, "{:.*}", #precision, #value)?;),
+			None => quote!(::core::write!(__f, "{}", #value)?;)
+		};
+		let element = vec_element(&field.ty);
+		if element.is_some() {
+			let separator = if layout.per_line { "\n" } else { " " };
+			let write_element = write_one(quote!(__element));
+			writes.push(quote! {
+				for (__index, __element) in #binding.iter().enumerate() {
+					if __index > 0 {
+						__f.write_str(#separator)?;
+					} else if __line_started {
+						__f.write_str(" ")?;
+					}
+					#write_element
+					__line_started = true;
+				}
+			});
+		} else {
+			let write_value = write_one(quote!(#binding));
+			writes.push(quote! {
+				if __line_started {
+					__f.write_str(" ")?;
+				}
+				#write_value
+				__line_started = true;
+			});
+		}
+
+		// reading back: a list per line runs until the end, otherwise until the end of the line
+		if index == 0 {
+			layout.newline = false;
+		}
+		layout.precision = None;
+		match element {
+			Some(element) if layout.per_line => {
+				let newline = layout.newline.then(|| quote!(__scanner.end_line()?;));
+				reads.push(quote! {
+					#newline
+					let #binding = ::shared::output::read_lines(__scanner, |__scanner| {
+						<#element as ::shared::input::KattisInput>::read(__scanner)
+					})?;
+				});
+			},
+			_ => reads.push(read_field(field, binding, &layout)?)
+		}
+	}
+
+	let writes = quote! {
+		let mut __line_started = false;
+		#(#writes)*
+	};
+	Ok((pattern, writes, quote!(#(#reads)*)))
+}
@@ -0,0 +1,111 @@
+//! Lists the problems in the repo with their metadata (see `shared::metadata`).
+//!
+//! Usage: `cargo run --bin index -- [directory] [--tag <tag>] [--difficulty <min>..<max>]
+//! [--untagged]`
+//!
+//! The directory defaults to the current one. With `--tag` and `--difficulty` only the matching
+//! problems are listed (problems of unknown difficulty never match a difficulty range),
+//! `--untagged` lists the problems without tags or without a metadata file at all.
+
+use std::{
+	env,
+	path::{Path, PathBuf},
+	process::exit
+};
+
+use shared::metadata::{find_problems, Metadata};
+
+fn usage() -> ! {
+	eprintln!(
+		"Usage: index [directory] [--tag <tag>] [--difficulty <min>..<max>] [--untagged]"
+	);
+	exit(2)
+}
+
+fn difficulty_range(range: &str) -> Option<(f64, f64)> {
+	let (min, max) = range.split_once("..")?;
+	let bound = |bound: &str, default: f64| match bound.trim() {
+		"" => Some(default),
+		bound => bound.parse().ok()
+	};
+	Some((bound(min, 0.0)?, bound(max, f64::INFINITY)?))
+}
+
+fn optional(value: Option<impl ToString>) -> String {
+	value.map_or("~".to_string(), |value| value.to_string())
+}
+
+fn main() {
+	let mut arguments = env::args().skip(1);
+	let mut root = None;
+	let mut tag = None;
+	let mut difficulty = None;
+	let mut untagged = false;
+	while let Some(argument) = arguments.next() {
+		match argument.as_str() {
+			"--tag" => tag = Some(arguments.next().unwrap_or_else(|| usage())),
+			"--difficulty" => {
+				difficulty = Some(
+					arguments
+						.next()
+						.and_then(|range| difficulty_range(&range))
+						.unwrap_or_else(|| usage())
+				);
+			},
+			"--untagged" => untagged = true,
+			_ if root.is_none() => root = Some(PathBuf::from(argument)),
+			_ => usage()
+		}
+	}
+	let root = root.unwrap_or_else(|| PathBuf::from("."));
+
+	let mut listed = 0;
+	for problem in find_problems(&root) {
+		let name = problem.strip_prefix(&root).unwrap_or(&problem);
+		let metadata = match Metadata::read(&problem) {
+			Some(Ok(metadata)) => Some(metadata),
+			Some(Err(e)) => {
+				eprintln!("{}: invalid metadata: {}", name.display(), e);
+				continue;
+			},
+			None => None
+		};
+		let matches = match &metadata {
+			None => untagged && tag.is_none() && difficulty.is_none(),
+			Some(metadata) => {
+				(!untagged || metadata.tags.is_empty())
+					&& tag.as_ref().is_none_or(|tag| metadata.has_tag(tag))
+					&& difficulty.is_none_or(|(min, max)| {
+						metadata
+							.difficulty
+							.is_some_and(|difficulty| (min..=max).contains(&difficulty))
+					})
+			}
+		};
+		if matches {
+			print_problem(name, metadata.as_ref());
+			listed += 1;
+		}
+	}
+	println!("{} problems", listed);
+}
+
+fn print_problem(name: &Path, metadata: Option<&Metadata>) {
+	let Some(metadata) = metadata else {
+		println!("{}: no metadata", name.display());
+		return;
+	};
+	println!(
+		"{}: difficulty {}, time limit {}, memory limit {}, tags [{}]",
+		name.display(),
+		optional(metadata.difficulty),
+		optional(metadata.time_limit.map(|time| format!("{}s", time.as_secs_f64()))),
+		optional(metadata.memory_limit.map(|memory| format!("{} MiB", memory))),
+		metadata.tags.join(", ")
+	);
+	for (key, value) in [("source", &metadata.source), ("contest", &metadata.contest)] {
+		if let Some(value) = value {
+			println!("  {}: {}", key, value);
+		}
+	}
+}
@@ -0,0 +1,234 @@
+//! Builds a problem crate and runs it against its samples.
+//!
+//! Usage: `cargo run --bin judge -- <problem directory> [options]`, with the options
+//! `--time-limit <seconds>`, `--memory-limit <MiB>`, `--query-limit <n>`, `--solver <name>`
+//! and `--profile`.
+//!
+//! Problems with a `src/bin/interactor.rs` are judged interactively:
+//! the interactor is started as `interactor <input> <answer>`, talks to the solution
+//! over stdin/stdout, and reports the verdict with the exit codes in `shared::verdict`.
+//! Other problems are checked with their `src/bin/validator.rs` if they have one
+//! (see `shared::validator`), and compared token by token against the answer otherwise.
+//!
+//! With `--profile`, solutions using `shared::driver::run` write a profile per sample
+//! (see `shared::profile`), which is printed with the sample and added up at the end.
+//! Solutions with their own `main` have no profile.
+//! Problems with several solvers judge the one given with `--solver` (see `shared::solvers`).
+//! Without `--time-limit`, the time limit in the `problem.yml` of the problem is used
+//! (see `shared::metadata`), and one second if that does not have one either.
+//! The memory limit comes from there too, and is only enforced when one is known.
+//! Every problem is built in its own `target` directory, whatever `CARGO_TARGET_DIR` says.
+
+use std::{
+	env, fs,
+	path::{Path, PathBuf},
+	process::{exit, Command},
+	time::Duration
+};
+
+use shared::{
+	judge::{find_samples, run_batch, run_interactive, Checker, Limits, Outcome},
+	metadata::Metadata,
+	profile::{self, Profile},
+	solvers
+};
+
+fn usage() -> ! {
+	eprintln!(
+		"Usage: judge <problem directory> [--time-limit <seconds>] [--memory-limit <MiB>] \
+		 [--query-limit <n>] [--solver <name>] [--profile]"
+	);
+	exit(2)
+}
+
+/// `key: value` from a kattis.yml (or any other flat yaml file).
+fn yaml_value(path: &Path, key: &str) -> Option<String> {
+	let content = fs::read_to_string(path).ok()?;
+	content.lines().find_map(|line| {
+		let (line_key, value) = line.split_once(':')?;
+		(line_key.trim() == key).then(|| value.trim().to_string())
+	})
+}
+
+/// The package name in a Cargo.toml, which is also the name of its main binary.
+fn package_name(manifest: &Path) -> Option<String> {
+	let content = fs::read_to_string(manifest).ok()?;
+	content
+		.lines()
+		.skip_while(|line| line.trim() != "[package]")
+		.find_map(|line| {
+			let (key, value) = line.split_once('=')?;
+			(key.trim() == "name").then(|| value.trim().trim_matches('"').to_string())
+		})
+}
+
+fn print_profile(profile: &Profile) {
+	for phase in profile.phases.iter() {
+		let allocations = match (phase.allocations, phase.bytes) {
+			(Some(count), Some(bytes)) => format!(", {} allocations ({} bytes)", count, bytes),
+			_ => String::new()
+		};
+		println!(
+			"  {:>10}: {:.3}ms{}",
+			phase.phase.to_string(),
+			phase.time.as_secs_f64() * 1000.0,
+			allocations
+		);
+	}
+}
+
+fn main() {
+	let mut arguments = env::args().skip(1);
+	let mut problem = None;
+	let mut limits = Limits::default();
+	let mut time_limit = None;
+	let mut memory_limit = None;
+	let mut solver = None;
+	let mut profiled = false;
+	while let Some(argument) = arguments.next() {
+		match argument.as_str() {
+			"--time-limit" => {
+				let seconds: f64 = arguments
+					.next()
+					.and_then(|value| value.parse().ok())
+					.unwrap_or_else(|| usage());
+				time_limit = Some(Duration::from_secs_f64(seconds));
+			},
+			"--memory-limit" => {
+				memory_limit = Some(
+					arguments
+						.next()
+						.and_then(|value| value.parse().ok())
+						.unwrap_or_else(|| usage())
+				);
+			},
+			"--query-limit" => {
+				limits.queries = Some(
+					arguments
+						.next()
+						.and_then(|value| value.parse().ok())
+						.unwrap_or_else(|| usage())
+				);
+			},
+			"--solver" => solver = Some(arguments.next().unwrap_or_else(|| usage())),
+			"--profile" => profiled = true,
+			_ if problem.is_none() => problem = Some(PathBuf::from(argument)),
+			_ => usage()
+		}
+	}
+	let problem = problem.unwrap_or_else(|| usage());
+	let metadata = match Metadata::read(&problem) {
+		Some(Ok(metadata)) => Some(metadata),
+		Some(Err(e)) => {
+			eprintln!("Could not read the metadata of {}: {}", problem.display(), e);
+			exit(2)
+		},
+		None => None
+	};
+	if let Some(time) = time_limit.or(metadata.as_ref().and_then(|metadata| metadata.time_limit)) {
+		limits.time = time;
+	}
+	limits.memory = memory_limit.or(metadata.and_then(|metadata| metadata.memory_limit));
+
+	let manifest = problem.join("Cargo.toml");
+	let name = package_name(&manifest).unwrap_or_else(|| {
+		eprintln!("No package name in {}", manifest.display());
+		exit(2)
+	});
+	let samples_directory =
+		problem.join(yaml_value(&problem.join("kattis.yml"), "samples").unwrap_or("src".into()));
+	let interactive = problem.join("src/bin/interactor.rs").is_file();
+	let validated = problem.join("src/bin/validator.rs").is_file();
+
+	// several problems share a package name, so with a shared CARGO_TARGET_DIR their binaries
+	// would overwrite each other: every problem is built into its own target directory
+	let target = problem.join("target");
+	eprintln!("Building {}...", name);
+	let built = Command::new("cargo")
+		.args(["build", "--release", "--bins", "--quiet", "--manifest-path"])
+		.arg(&manifest)
+		.arg("--target-dir")
+		.arg(&target)
+		.status()
+		.is_ok_and(|status| status.success());
+	if !built {
+		eprintln!("Compile Error");
+		exit(1);
+	}
+	let binaries = target.join("release");
+	let profiles_directory = binaries.join("profiles");
+	let _ = fs::create_dir_all(&profiles_directory);
+
+	let samples = find_samples(&samples_directory).unwrap_or_else(|e| {
+		eprintln!("Could not read samples in {}: {}", samples_directory.display(), e);
+		exit(2)
+	});
+	if samples.is_empty() {
+		eprintln!("No samples found in {}", samples_directory.display());
+		exit(2);
+	}
+
+	let mut accepted = 0;
+	let mut profiles = Vec::new();
+	for sample in samples.iter() {
+		let report = profiles_directory.join(format!("{}.txt", sample.name));
+		let _ = fs::remove_file(&report);
+		let mut solution = Command::new(binaries.join(&name));
+		if profiled {
+			solution.env(profile::REPORT_VARIABLE, &report);
+		}
+		if let Some(solver) = &solver {
+			solution.env(solvers::SOLVER_VARIABLE, solver);
+		}
+		let run = if interactive {
+			let mut interactor = Command::new(binaries.join("interactor"));
+			interactor.arg(&sample.input).arg(&sample.answer);
+			run_interactive(&mut solution, &mut interactor, limits)
+		} else {
+			let input = fs::read_to_string(&sample.input).unwrap_or_default();
+			let answer = fs::read_to_string(&sample.answer).unwrap_or_default();
+			let mut validator = Command::new(binaries.join("validator"));
+			validator.arg(&sample.input).arg(&sample.answer);
+			let checker = if validated {
+				Checker::Validator(&mut validator)
+			} else {
+				Checker::Compare
+			};
+			run_batch(&mut solution, &input, &answer, checker, limits)
+		};
+		println!(
+			"{}: {} ({:.3}s)",
+			sample.name,
+			run.outcome,
+			run.time.as_secs_f64()
+		);
+		if let Some(profile) = fs::read_to_string(&report)
+			.ok()
+			.and_then(|report| report.parse::<Profile>().ok())
+		{
+			print_profile(&profile);
+			profiles.push(profile);
+		}
+		match run.outcome {
+			Outcome::Accepted => accepted += 1,
+			Outcome::RunTimeError(_) => {
+				// the panic message is usually at the very end
+				let lines: Vec<_> = run.stderr.lines().collect();
+				for line in &lines[lines.len().saturating_sub(10)..] {
+					println!("  | {}", line);
+				}
+			},
+			_ => {}
+		}
+	}
+	if !profiles.is_empty() {
+		println!("total of {} profiled samples:", profiles.len());
+		print_profile(&Profile::total(&profiles));
+	} else if profiled {
+		println!("no profiles, only solutions using `shared::driver::run` write them");
+	}
+	println!("{}/{} samples accepted", accepted, samples.len());
+	if accepted != samples.len() {
+		exit(1);
+	}
+}
@@ -0,0 +1,405 @@
+//! Inputs made of several independent test cases, solved one by one.
+
+use std::{
+	fmt::{Display, Formatter},
+	str::FromStr,
+	thread
+};
+
+use crate::{
+	diagnostics::{self, Phase},
+	input::{self, KattisInput},
+	output,
+	scanner::{ParseError, Scanner}
+};
+
+/// How the list of cases ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+	/// The first line holds the number of cases.
+	Counted,
+	/// Cases until the end of the input.
+	Eof,
+	/// Cases until a line with exactly these tokens (like "0 0"), however they are spaced.
+	Sentinel(&'static str)
+}
+
+/// What goes in front of every solved case.
+#[derive(Debug, Clone, Copy)]
+pub enum Prefix {
+	None,
+	/// "Case #1: answer"
+	CaseNumber,
+	/// "Case #1:" on its own line, with the answer below
+	CaseNumberLine,
+	/// Anything else, given the 1-indexed case number
+	Custom(fn(usize) -> String)
+}
+impl Prefix {
+	pub fn render(&self, case_number: usize) -> String {
+		match self {
+			Prefix::None => String::new(),
+			Prefix::CaseNumber => format!("Case #{}: ", case_number),
+			Prefix::CaseNumberLine => format!("Case #{}:\n", case_number),
+			Prefix::Custom(render) => render(case_number)
+		}
+	}
+}
+
+/// A single case of a multi-case problem.
+/// Every case starts on a new line, blank lines between cases are skipped.
+pub trait TestCase: KattisInput {
+	const TERMINATION: Termination = Termination::Counted;
+	const PREFIX: Prefix = Prefix::CaseNumber;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cases<P> {
+	pub cases: Vec<P>,
+	starts:    Vec<usize> // byte offset of every case in the input, for diagnostics
+}
+
+impl<P: TestCase> KattisInput for Cases<P> {
+	fn read(scanner: &mut Scanner<'_>) -> Result<Self, ParseError> {
+		let mut cases = Vec::new();
+		let mut starts = Vec::new();
+		let mut read_case = |scanner: &mut Scanner<'_>| {
+			diagnostics::set_case(Some(cases.len() + 1));
+			starts.push(scanner.offset());
+			cases.push(P::read(scanner)?);
+			Ok(())
+		};
+		match P::TERMINATION {
+			Termination::Counted => {
+				let count: usize = scanner.token()?;
+				for _ in 0..count {
+					next_case(scanner)?;
+					read_case(scanner)?;
+				}
+			},
+			Termination::Eof => {
+				scanner.skip_whitespace();
+				for index in 0.. {
+					if scanner.at_end() {
+						break;
+					}
+					if index > 0 {
+						next_case(scanner)?;
+					}
+					read_case(scanner)?;
+				}
+			},
+			Termination::Sentinel(sentinel) => {
+				for index in 0.. {
+					if index == 0 {
+						scanner.skip_whitespace();
+					} else {
+						next_case(scanner)?;
+					}
+					if scanner.peek_line().split_whitespace().eq(sentinel.split_whitespace()) {
+						scanner.line()?;
+						break;
+					}
+					read_case(scanner)?;
+				}
+			}
+		}
+		diagnostics::set_case(None);
+		Ok(Cases { cases, starts })
+	}
+}
+
+impl<P: TestCase> FromStr for Cases<P> {
+	type Err = ParseError;
+
+	fn from_str(input: &str) -> Result<Self, Self::Err> {
+		input::parse(input)
+	}
+}
+
+/// Moves on to the line where the next case starts.
+fn next_case(scanner: &mut Scanner<'_>) -> Result<(), ParseError> {
+	scanner.end_line()?;
+	scanner.skip_whitespace();
+	Ok(())
+}
+
+/// Points the diagnostics at the case about to be solved.
+fn solving(case_number: usize, start: usize) {
+	diagnostics::set_phase(Phase::Solving);
+	diagnostics::set_case(Some(case_number));
+	diagnostics::set_offset(start);
+}
+
+impl<P: TestCase> Cases<P> {
+	pub fn new(cases: Vec<P>) -> Self {
+		let starts = vec![0; cases.len()];
+		Cases { cases, starts }
+	}
+
+	pub fn solve<S: From<P>>(self) -> Solved<S> {
+		let solutions = self
+			.cases
+			.into_iter()
+			.zip(self.starts)
+			.enumerate()
+			.map(|(index, (case, start))| {
+				solving(index + 1, start);
+				S::from(case)
+			})
+			.collect();
+		diagnostics::set_case(None);
+		Solved {
+			prefix: P::PREFIX,
+			solutions
+		}
+	}
+
+	/// Same as `solve`, but spreads the cases over all cores.
+	/// Meant for running big local tests faster, the order of the answers is kept.
+	pub fn solve_parallel<S>(self) -> Solved<S>
+	where
+		P: Send,
+		S: From<P> + Send
+	{
+		let threads = thread::available_parallelism().map_or(1, |n| n.get());
+		let chunk_size = self.cases.len().div_ceil(threads).max(1);
+		let mut cases = self.cases.into_iter().zip(self.starts).enumerate();
+		let chunks: Vec<Vec<_>> = (0..threads)
+			.map(|_| cases.by_ref().take(chunk_size).collect())
+			.collect();
+		let input = diagnostics::input();
+		let solutions = thread::scope(|scope| {
+			let handles: Vec<_> = chunks
+				.into_iter()
+				.map(|chunk| {
+					let input = input.clone();
+					scope.spawn(move || {
+						diagnostics::share_input(input);
+						chunk
+							.into_iter()
+							.map(|(index, (case, start))| {
+								solving(index + 1, start);
+								S::from(case)
+							})
+							.collect::<Vec<_>>()
+					})
+				})
+				.collect();
+			handles
+				.into_iter()
+				.flat_map(|handle| handle.join().expect("A case panicked while solving!"))
+				.collect()
+		});
+		Solved {
+			prefix: P::PREFIX,
+			solutions
+		}
+	}
+}
+
+/// The answers to every case, written one after another with their prefixes.
+#[derive(Debug, Clone)]
+pub struct Solved<S> {
+	pub prefix:    Prefix,
+	pub solutions: Vec<S>
+}
+
+impl<S: Display> Display for Solved<S> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for (index, solution) in self.solutions.iter().enumerate() {
+			if index > 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}{}", self.prefix.render(index + 1), solution)?;
+		}
+		Ok(())
+	}
+}
+
+impl<S: KattisInput> Solved<S> {
+	/// Reads an answer file with the given prefixes, to compare against a sample.
+	pub fn parse(answer: &str, prefix: Prefix) -> Result<Self, ParseError> {
+		let mut scanner = Scanner::new(answer);
+		let mut solutions = Vec::new();
+		scanner.skip_whitespace();
+		while !scanner.at_end() {
+			if !solutions.is_empty() {
+				next_case(&mut scanner)?;
+			}
+			let rendered = prefix.render(solutions.len() + 1);
+			output::literal(&mut scanner, &rendered)?;
+			if rendered.ends_with('\n') {
+				scanner.end_line()?;
+			}
+			solutions.push(S::read(&mut scanner)?);
+		}
+		Ok(Solved { prefix, solutions })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		cases::{Cases, Prefix, Solved, TestCase, Termination},
+		scanner::Position,
+		KattisInput
+	};
+
+	// alien-numerals: T, then one case per line
+	#[derive(Debug, KattisInput)]
+	struct AlienNumber {
+		alien_number:    String,
+		source_language: String,
+		target_language: String
+	}
+	impl TestCase for AlienNumber {}
+
+	// reachableroads: count, then cities spanning several lines each
+	#[derive(Debug, KattisInput)]
+	struct Road {
+		a: usize,
+		b: usize
+	}
+	#[derive(Debug, KattisInput)]
+	struct City {
+		endpoint_count: usize,
+		#[kattis(newline)]
+		_road_count:    usize,
+		#[kattis(count = _road_count, per_line)]
+		roads:          Vec<Road>
+	}
+	impl TestCase for City {
+		const PREFIX: Prefix = Prefix::None;
+	}
+
+	#[derive(Debug, PartialEq, KattisInput)]
+	struct Pair {
+		a: i64,
+		b: i64
+	}
+	impl TestCase for Pair {
+		const PREFIX: Prefix = Prefix::None;
+		const TERMINATION: Termination = Termination::Sentinel("0 0");
+	}
+
+	#[derive(Debug, PartialEq, KattisInput)]
+	struct Word {
+		word: String
+	}
+	impl TestCase for Word {
+		const PREFIX: Prefix = Prefix::Custom(|case| format!("{}. ", case));
+		const TERMINATION: Termination = Termination::Eof;
+	}
+
+	struct Sum(i64);
+	impl From<Pair> for Sum {
+		fn from(pair: Pair) -> Self {
+			Sum(pair.a + pair.b)
+		}
+	}
+	impl std::fmt::Display for Sum {
+		fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+			write!(f, "{}", self.0)
+		}
+	}
+
+	#[test]
+	fn alien_numerals_sample() {
+		let input = include_str!("../../kattis/challenges/alien-numerals/src/sample.in");
+		let cases: Cases<AlienNumber> = input.parse().unwrap();
+		assert_eq!(cases.cases.len(), 4);
+		assert_eq!(cases.cases[3].alien_number, "CODE");
+		assert_eq!(cases.cases[3].target_language, "A?JM!.");
+		assert_eq!(cases.cases[1].source_language, "oF8");
+	}
+
+	#[test]
+	fn alien_numerals_answer() {
+		let answer = include_str!("../../kattis/challenges/alien-numerals/src/sample.ans");
+		let solved = Solved::<String>::parse(answer, Prefix::CaseNumber).unwrap();
+		assert_eq!(solved.solutions[0], "Foo");
+		assert_eq!(solved.to_string(), answer.trim());
+	}
+
+	#[test]
+	fn reachableroads_sample() {
+		let input = include_str!("../../kattis/reachableroads/src/in.txt");
+		let cities: Cases<City> = input.parse().unwrap();
+		assert_eq!(cities.cases.len(), 2);
+		assert_eq!(cities.cases[0].endpoint_count, 5);
+		assert_eq!(cities.cases[0].roads.len(), 3);
+		assert_eq!((cities.cases[0].roads[2].a, cities.cases[0].roads[2].b), (3, 4));
+		assert_eq!(cities.cases[1].roads.len(), 1);
+	}
+
+	#[test]
+	fn blank_lines_between_cases() {
+		let input = "2\n5\n1\n0 1\n\n\n3\n0\n";
+		let cities: Cases<City> = input.parse().unwrap();
+		assert_eq!(cities.cases[1].endpoint_count, 3);
+	}
+
+	#[test]
+	fn too_few_cases() {
+		let error = "3\n5\n0\n".parse::<Cases<City>>().unwrap_err();
+		assert_eq!(error.position, Position { line: 4, column: 1 });
+	}
+
+	#[test]
+	fn sentinel_terminated() {
+		let cases: Cases<Pair> = "1 2\n-3 4\n0 0\n".parse().unwrap();
+		assert_eq!(cases.cases, vec![Pair { a: 1, b: 2 }, Pair { a: -3, b: 4 }]);
+		assert_eq!(cases.solve::<Sum>().to_string(), "3\n1");
+		assert!("1 2\n".parse::<Cases<Pair>>().is_err());
+	}
+
+	#[test]
+	fn sentinel_spacing() {
+		for sentinel in ["0  0", "0 0 \t", "\t0\t0", "0 0\r"] {
+			let input = format!("1 2\n{}\n", sentinel);
+			let cases: Cases<Pair> = input.parse().unwrap_or_else(|e| panic!("{:?}: {}", input, e));
+			assert_eq!(cases.cases, vec![Pair { a: 1, b: 2 }], "{:?}", input);
+		}
+		// a longer line is a case, which then has too many numbers
+		assert!("1 2\n0 0 0\n0 0\n".parse::<Cases<Pair>>().is_err());
+	}
+
+	#[test]
+	fn eof_terminated() {
+		let cases: Cases<Word> = "\na\nb\n\nc\n".parse().unwrap();
+		assert_eq!(cases.cases.len(), 3);
+		let solved = Solved {
+			prefix:    Word::PREFIX,
+			solutions: cases.cases.iter().map(|word| word.word.clone()).collect()
+		};
+		assert_eq!(solved.to_string(), "1. a\n2. b\n3. c");
+	}
+
+	#[test]
+	fn prefix_on_own_line() {
+		let solved = Solved {
+			prefix:    Prefix::CaseNumberLine,
+			solutions: vec![1, 2]
+		};
+		assert_eq!(solved.to_string(), "Case #1:\n1\nCase #2:\n2");
+		let parsed = Solved::<usize>::parse(&solved.to_string(), Prefix::CaseNumberLine).unwrap();
+		assert_eq!(parsed.solutions, vec![1, 2]);
+	}
+
+	#[test]
+	fn parallel_keeps_order() {
+		let input: String = (1..1000)
+			.map(|i| format!("{} {}\n", i, i))
+			.chain(["0 0\n".to_string()])
+			.collect();
+		let cases: Cases<Pair> = input.parse().unwrap();
+		let sequential = cases
+			.cases
+			.iter()
+			.map(|pair| (pair.a + pair.b).to_string())
+			.collect::<Vec<_>>()
+			.join("\n");
+		assert_eq!(cases.solve_parallel::<Sum>().to_string(), sequential);
+	}
+}
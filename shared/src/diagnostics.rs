@@ -0,0 +1,256 @@
+//! Debug-only panic hook that tells where in the input things went wrong.
+//!
+//! The scanner, `Cases` and the driver keep a thread-local context up to date:
+//! the input, how far it has been read (or where the current case started, while solving),
+//! the case number and the phase. Solutions with their own parser call `set_input` and
+//! `point_at` instead. On a panic the hook prints it below the usual message:
+//! ```text
+//! --- PANIC CONTEXT:
+//! case #2, while solving
+//! line 4, column 1:
+//!     4 | 3 7 x
+//!       | ^
+//! ```
+//! The input is only kept once `install` was called, so reading without the hook copies nothing.
+//! In release builds none of this is kept and `install` does nothing.
+
+#[cfg(debug_assertions)]
+use std::{
+	cell::RefCell,
+	panic,
+	sync::atomic::{AtomicBool, Ordering}
+};
+use std::{
+	fmt::{Display, Formatter},
+	sync::Arc
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+	Parsing,
+	Solving,
+	Formatting
+}
+impl Display for Phase {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Phase::Parsing => write!(f, "parsing"),
+			Phase::Solving => write!(f, "solving"),
+			Phase::Formatting => write!(f, "formatting")
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+#[cfg_attr(not(debug_assertions), allow(dead_code))]
+struct Context {
+	input:  Option<Arc<str>>,
+	offset: usize, // byte offset into input
+	case:   Option<usize>,
+	phase:  Option<Phase>
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+	static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+// whether the hook is there, for every thread
+#[cfg(debug_assertions)]
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+fn update(change: impl FnOnce(&mut Context)) {
+	#[cfg(debug_assertions)]
+	CONTEXT.with_borrow_mut(change);
+}
+
+/// Adds the context to the panic message of every thread. Installing it again does nothing.
+pub fn install() {
+	#[cfg(debug_assertions)]
+	{
+		if INSTALLED.swap(true, Ordering::Relaxed) {
+			return;
+		}
+		let previous = panic::take_hook();
+		panic::set_hook(Box::new(move |info| {
+			previous(info);
+			if let Some(report) = report() {
+				eprintln!("--- PANIC CONTEXT:\n{}", report);
+			}
+		}));
+	}
+}
+
+pub fn set_phase(phase: Phase) {
+	update(|context| context.phase = Some(phase));
+}
+
+/// 1-indexed case number, `None` when not in a multi-case input (anymore).
+pub fn set_case(case: Option<usize>) {
+	update(|context| context.case = case);
+}
+
+/// Starts tracking a new input, done by `Scanner::new`. Only copies it when the hook is installed.
+#[cfg_attr(not(debug_assertions), allow(unused_variables))]
+pub fn set_input(input: &str) {
+	#[cfg(debug_assertions)]
+	{
+		let input = INSTALLED.load(Ordering::Relaxed).then(|| Arc::from(input));
+		update(|context| {
+			context.input = input;
+			context.offset = 0;
+		});
+	}
+}
+
+/// The input of this thread, to hand to threads solving parts of it.
+pub fn input() -> Option<Arc<str>> {
+	#[cfg(debug_assertions)]
+	{
+		CONTEXT.with_borrow(|context| context.input.clone())
+	}
+	#[cfg(not(debug_assertions))]
+	None
+}
+
+/// Tracks the same input as another thread, see `input`.
+pub fn share_input(input: Option<Arc<str>>) {
+	update(|context| {
+		context.input = input;
+		context.offset = 0;
+	});
+}
+
+/// Byte offset into the input that is being looked at.
+pub fn set_offset(offset: usize) {
+	update(|context| context.offset = offset);
+}
+
+/// Points at `part`, a slice of `input`, for parsers that split the input themselves
+/// instead of using the scanner. A `part` from anywhere else is ignored.
+pub fn point_at(input: &str, part: &str) {
+	let offset = (part.as_ptr() as usize).wrapping_sub(input.as_ptr() as usize);
+	if offset <= input.len() {
+		set_offset(offset);
+	}
+}
+
+/// The context of this thread, `None` if there is none (or it is a release build).
+pub fn report() -> Option<String> {
+	#[cfg(debug_assertions)]
+	{
+		// may be called while the context is borrowed, if the panic came from in here
+		CONTEXT
+			.try_with(|context| context.try_borrow().ok().and_then(|context| context.render()))
+			.ok()
+			.flatten()
+	}
+	#[cfg(not(debug_assertions))]
+	None
+}
+
+#[cfg(debug_assertions)]
+impl Context {
+	fn render(&self) -> Option<String> {
+		let mut lines = Vec::new();
+		match (self.case, self.phase) {
+			(Some(case), Some(phase)) => lines.push(format!("case #{}, while {}", case, phase)),
+			(Some(case), None) => lines.push(format!("case #{}", case)),
+			(None, Some(phase)) => lines.push(format!("while {}", phase)),
+			(None, None) => {}
+		}
+		if let Some(input) = &self.input {
+			let offset = self.offset.min(input.len());
+			let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+			let line_end = input[offset..].find('\n').map_or(input.len(), |index| offset + index);
+			let line_number = input[..offset].matches('\n').count() + 1;
+			let column = input[line_start..offset].chars().count() + 1;
+			let number = line_number.to_string();
+			lines.push(format!("line {}, column {}:", line_number, column));
+			lines.push(format!(
+				"    {} | {}",
+				number,
+				input[line_start..line_end].trim_end_matches('\r')
+			));
+			lines.push(format!(
+				"    {} | {}^",
+				" ".repeat(number.len()),
+				" ".repeat(column - 1)
+			));
+		}
+		(!lines.is_empty()).then(|| lines.join("\n"))
+	}
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+	use crate::{
+		cases::{Cases, TestCase},
+		diagnostics::{install, point_at, report, set_case, set_input, set_phase, Phase},
+		scanner::Scanner,
+		KattisInput
+	};
+
+	#[derive(KattisInput)]
+	struct Pair {
+		a: u32,
+		b: u32
+	}
+	impl TestCase for Pair {}
+
+	// every test runs on its own thread, so the contexts do not mix.
+	// the input is only kept with the hook installed
+	#[test]
+	fn points_at_token_being_read() {
+		install();
+		let mut scanner = Scanner::new("1 2\n3 x\n");
+		scanner.token::<u32>().unwrap();
+		scanner.token::<u32>().unwrap();
+		scanner.end_line().unwrap();
+		scanner.token::<u32>().unwrap();
+		assert!(scanner.token::<u32>().is_err());
+		assert_eq!(report().unwrap(), "line 2, column 3:\n    2 | 3 x\n      |   ^");
+	}
+
+	#[test]
+	fn points_at_slice_of_input() {
+		install();
+		let input = "1 2\n3 x\n";
+		set_input(input);
+		let bad = input.split_whitespace().find(|token| token.parse::<u32>().is_err());
+		point_at(input, bad.unwrap());
+		assert_eq!(report().unwrap(), "line 2, column 3:\n    2 | 3 x\n      |   ^");
+		// a copy is not part of the input
+		let copy = String::from("1");
+		point_at(input, &copy);
+		assert_eq!(report().unwrap(), "line 2, column 3:\n    2 | 3 x\n      |   ^");
+	}
+
+	#[test]
+	fn case_and_phase() {
+		assert_eq!(report(), None);
+		set_phase(Phase::Solving);
+		assert_eq!(report().unwrap(), "while solving");
+		set_case(Some(12));
+		assert_eq!(report().unwrap(), "case #12, while solving");
+	}
+
+	impl From<Pair> for u32 {
+		fn from(pair: Pair) -> Self {
+			pair.a / pair.b
+		}
+	}
+
+	#[test]
+	fn solving_points_at_start_of_case() {
+		install();
+		let cases: Cases<Pair> = "3\n4 2\n1 0\n9 3\n".parse().unwrap();
+		let solving = std::panic::catch_unwind(|| cases.solve::<u32>());
+		assert!(solving.is_err());
+		assert_eq!(
+			report().unwrap(),
+			"case #2, while solving\nline 3, column 1:\n    3 | 1 0\n      | ^"
+		);
+	}
+}
@@ -0,0 +1,234 @@
+//! Graphviz DOT export for adjacency structures, to look at a graph instead of a `{:?}` dump.
+//!
+//! ```ignore
+//! let mut dot = Dot::from_adjacency("houses", Kind::Undirected, &connections);
+//! for (id, state) in states {
+//!     dot.color(id, state.color());
+//! }
+//! dot.write("sample_1"); // $DOT_DIRECTORY/sample_1.dot
+//! ```
+//! Render with `dot -Tsvg sample_1.dot -o sample_1.svg`.
+//! Files are only written in debug builds with `DOT_DIRECTORY` set, so tests can always call
+//! `write` and only leave files behind when asked to.
+
+use std::{
+	collections::{BTreeMap, HashSet},
+	fmt::{Display, Formatter},
+	io,
+	path::PathBuf
+};
+
+pub const DIRECTORY_VARIABLE: &str = "DOT_DIRECTORY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+	Directed,
+	Undirected
+}
+
+/// The state of a node, shown as its fill color.
+pub trait NodeState {
+	/// Any color Graphviz knows, like `"palegreen"` or `"#ff8800"`.
+	fn color(&self) -> &'static str;
+}
+
+/// Distinct colors for numbered groups, like the components of a graph.
+pub fn palette(index: usize) -> &'static str {
+	const COLORS: [&str; 8] = [
+		"lightblue",
+		"palegreen",
+		"lightsalmon",
+		"plum",
+		"khaki",
+		"lightpink",
+		"aquamarine",
+		"tan"
+	];
+	COLORS[index % COLORS.len()]
+}
+
+#[derive(Debug, Clone)]
+pub struct Dot {
+	name:  String,
+	kind:  Kind,
+	// node name to fill color, in a BTreeMap so the output does not change between runs
+	nodes: BTreeMap<String, Option<&'static str>>,
+	edges: Vec<(String, String)>,
+	// undirected edges drawn so far, smaller name first
+	drawn: HashSet<(String, String)>
+}
+
+impl Dot {
+	pub fn new(name: impl Into<String>, kind: Kind) -> Self {
+		Dot {
+			name: name.into(),
+			kind,
+			nodes: BTreeMap::new(),
+			edges: Vec::new(),
+			drawn: HashSet::new()
+		}
+	}
+
+	/// A node with its neighbours for every node, like a `BTreeMap<ID, BTreeSet<ID>>`
+	/// or a `Vec<Vec<usize>>` (through `.iter().enumerate()`).
+	/// Undirected edges listed from both ends are only drawn once.
+	pub fn from_adjacency<N, E>(
+		name: impl Into<String>,
+		kind: Kind,
+		adjacency: impl IntoIterator<Item = (N, E)>
+	) -> Self
+	where
+		N: Display,
+		E: IntoIterator,
+		E::Item: Display
+	{
+		let mut dot = Dot::new(name, kind);
+		for (node, neighbours) in adjacency {
+			dot.node(&node);
+			for neighbour in neighbours {
+				dot.edge(&node, neighbour);
+			}
+		}
+		dot
+	}
+
+	pub fn node(&mut self, node: impl Display) -> &mut Self {
+		self.nodes.entry(node.to_string()).or_insert(None);
+		self
+	}
+
+	pub fn color(&mut self, node: impl Display, color: &'static str) -> &mut Self {
+		self.nodes.insert(node.to_string(), Some(color));
+		self
+	}
+
+	pub fn state(&mut self, node: impl Display, state: &impl NodeState) -> &mut Self {
+		self.color(node, state.color())
+	}
+
+	pub fn edge(&mut self, from: impl Display, to: impl Display) -> &mut Self {
+		let (from, to) = (from.to_string(), to.to_string());
+		let duplicate = self.kind == Kind::Undirected && {
+			let pair = if from <= to {
+				(from.clone(), to.clone())
+			} else {
+				(to.clone(), from.clone())
+			};
+			!self.drawn.insert(pair)
+		};
+		if !duplicate {
+			self.node(&from).node(&to);
+			self.edges.push((from, to));
+		}
+		self
+	}
+
+	/// Writes `<name>.dot` to `DOT_DIRECTORY`.
+	/// Does nothing when that is not set, and in release builds.
+	pub fn write(&self, name: &str) -> io::Result<Option<PathBuf>> {
+		if !cfg!(debug_assertions) {
+			return Ok(None);
+		}
+		let Some(directory) = std::env::var_os(DIRECTORY_VARIABLE).map(PathBuf::from) else {
+			return Ok(None);
+		};
+		std::fs::create_dir_all(&directory)?;
+		let path = directory.join(format!("{}.dot", name));
+		std::fs::write(&path, self.to_string())?;
+		Ok(Some(path))
+	}
+}
+
+fn quoted(text: &str) -> String {
+	format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Display for Dot {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let (keyword, arrow) = match self.kind {
+			Kind::Directed => ("digraph", "->"),
+			Kind::Undirected => ("graph", "--")
+		};
+		writeln!(f, "{} {} {{", keyword, quoted(&self.name))?;
+		for (node, color) in self.nodes.iter() {
+			match color {
+				Some(color) => writeln!(
+					f,
+					"    {} [style=filled, fillcolor={}];",
+					quoted(node),
+					quoted(color)
+				)?,
+				None => writeln!(f, "    {};", quoted(node))?
+			}
+		}
+		for (from, to) in self.edges.iter() {
+			writeln!(f, "    {} {} {};", quoted(from), arrow, quoted(to))?;
+		}
+		writeln!(f, "}}")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::{BTreeMap, BTreeSet};
+
+	use crate::dot::{Dot, Kind, NodeState};
+
+	enum Domino {
+		Fallen,
+		Standing
+	}
+	impl NodeState for Domino {
+		fn color(&self) -> &'static str {
+			match self {
+				Domino::Fallen => "lightsalmon",
+				Domino::Standing => "palegreen"
+			}
+		}
+	}
+
+	#[test]
+	fn undirected_edges_once() {
+		let adjacency = BTreeMap::from([
+			(1, BTreeSet::from([2])),
+			(2, BTreeSet::from([1, 3])),
+			(3, BTreeSet::from([2])),
+		]);
+		let mut dot = Dot::from_adjacency("houses", Kind::Undirected, &adjacency);
+		dot.color(1, "palegreen");
+		assert_eq!(
+			dot.to_string(),
+			"graph \"houses\" {\n    \"1\" [style=filled, fillcolor=\"palegreen\"];\n    \"2\";\n    \
+			 \"3\";\n    \"1\" -- \"2\";\n    \"2\" -- \"3\";\n}\n"
+		);
+	}
+
+	#[test]
+	fn directed_with_states() {
+		let lineups = [vec![1], vec![2], vec![]];
+		let mut dot = Dot::from_adjacency("dominoes", Kind::Directed, lineups.iter().enumerate());
+		dot.state(0, &Domino::Fallen).state(2, &Domino::Standing).edge(2, 0);
+		let dot = dot.to_string();
+		assert!(dot.starts_with("digraph \"dominoes\" {\n"), "{}", dot);
+		assert!(dot.contains("\"0\" [style=filled, fillcolor=\"lightsalmon\"];"), "{}", dot);
+		assert!(dot.contains("\"0\" -> \"1\";\n    \"1\" -> \"2\";\n    \"2\" -> \"0\";"), "{}", dot);
+	}
+
+	#[test]
+	fn many_undirected_edges() {
+		// every edge listed from both ends, which used to take quadratic time
+		let nodes = 200_000;
+		let around = |node| [(node + nodes - 1) % nodes, (node + 1) % nodes];
+		let adjacency = (0..nodes).map(|node| (node, around(node)));
+		let dot = Dot::from_adjacency("cycle", Kind::Undirected, adjacency);
+		assert_eq!(dot.to_string().matches(" -- ").count(), nodes);
+	}
+
+	#[test]
+	fn names_are_escaped() {
+		let mut dot = Dot::new("a \"quoted\" name", Kind::Directed);
+		dot.node("back\\slash");
+		assert!(dot.to_string().contains("\"a \\\"quoted\\\" name\""));
+		assert!(dot.to_string().contains("\"back\\\\slash\";"));
+	}
+}
@@ -0,0 +1,216 @@
+//! The usual `main` of a solution: read stdin, parse the problem, solve it, print the solution.
+//! Debug builds also get the panic context from `diagnostics`,
+//! and a run reports its phases when asked to (see `profile`).
+//! ```ignore
+//! fn main() {
+//!     shared::driver::run::<Problem, Solution>(Settings::default());
+//! }
+//! ```
+//! There is no bundler that inlines this into a submission, so the driver and its stack size are
+//! for local runs and tests only, like the recursive solvers in the wheresmyinternet tests.
+//! A submission that recursed that deep would have to spawn its own thread.
+
+use std::{
+	env,
+	fmt::Display,
+	fs,
+	io::{stdin, Read},
+	panic,
+	str::FromStr,
+	thread
+};
+
+use crate::{
+	diagnostics::{self, Phase},
+	normalize::{normalize, Whitespace},
+	profile::{self, Profile, Profiler}
+};
+
+/// Stack size that comfortably fits one recursion per node for 10^5-10^6 nodes.
+pub const BIG_STACK: usize = 512 << 20;
+
+/// How a problem wants to be run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Settings {
+	/// Solves on a thread with this much stack instead of on the main thread,
+	/// which only gets 8 MiB on most systems (1 MiB on Windows).
+	pub stack_size: Option<usize>,
+	/// How much of the input is cleaned up before parsing, see `normalize`.
+	pub whitespace: Whitespace
+}
+
+pub fn run<P, S>(settings: Settings)
+where
+	P: FromStr,
+	P::Err: Display,
+	S: From<P> + Display
+{
+	diagnostics::install();
+	let mut input = String::new();
+	stdin()
+		.read_to_string(&mut input)
+		.expect("Could not read from stdin()?");
+	let input = normalize(&input, settings.whitespace);
+	let report = env::var_os(profile::REPORT_VARIABLE);
+	let profiled = report.is_some();
+	let (output, profile) = with_stack(settings.stack_size, move || {
+		if profiled {
+			let (output, profile) = solve_profiled::<P, S>(&input);
+			(output, Some(profile))
+		} else {
+			(solve::<P, S>(&input), None)
+		}
+	});
+	println!("{}", output);
+	if let (Some(path), Some(profile)) = (report, profile) {
+		fs::write(&path, profile.to_string()).expect("Could not write profile report!");
+	}
+}
+
+/// Parses, solves and formats, without any I/O.
+pub fn solve<P, S>(input: &str) -> String
+where
+	P: FromStr,
+	P::Err: Display,
+	S: From<P> + Display
+{
+	phases::<P, S>(input, None)
+}
+
+/// `solve`, measuring every phase.
+pub fn solve_profiled<P, S>(input: &str) -> (String, Profile)
+where
+	P: FromStr,
+	P::Err: Display,
+	S: From<P> + Display
+{
+	let mut profiler = Profiler::start();
+	let output = phases::<P, S>(input, Some(&mut profiler));
+	(output, profiler.finish())
+}
+
+fn phases<P, S>(input: &str, mut profiler: Option<&mut Profiler>) -> String
+where
+	P: FromStr,
+	P::Err: Display,
+	S: From<P> + Display
+{
+	let mut end = |phase| {
+		if let Some(profiler) = profiler.as_mut() {
+			profiler.end(phase);
+		}
+	};
+	diagnostics::set_phase(Phase::Parsing);
+	let problem: P = input
+		.parse()
+		.unwrap_or_else(|e| panic!("Cannot parse problem! {}", e));
+	end(Phase::Parsing);
+	diagnostics::set_phase(Phase::Solving);
+	let solution = S::from(problem);
+	end(Phase::Solving);
+	diagnostics::set_phase(Phase::Formatting);
+	let output = solution.to_string();
+	end(Phase::Formatting);
+	output
+}
+
+/// Runs `f` on a thread with the given stack size, or right here with `None`.
+/// A panic in `f` is passed on as if it happened on this thread.
+pub fn with_stack<T, F>(stack_size: Option<usize>, f: F) -> T
+where
+	T: Send + 'static,
+	F: FnOnce() -> T + Send + 'static
+{
+	let Some(stack_size) = stack_size else {
+		return f();
+	};
+	let solver = thread::Builder::new()
+		.name("solver".to_string())
+		.stack_size(stack_size)
+		.spawn(f)
+		.expect("Could not spawn solver thread!");
+	solver
+		.join()
+		.unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{
+		fmt::{Display, Formatter},
+		thread
+	};
+
+	use crate::{
+		cases::{Cases, TestCase},
+		diagnostics::Phase,
+		driver::{solve, solve_profiled, with_stack, BIG_STACK},
+		KattisInput
+	};
+
+	fn depth(n: u64) -> u64 {
+		// keeps some of the stack busy every call, so this is not turned into a loop
+		let padding = std::hint::black_box([n; 16]);
+		if n == 0 {
+			0
+		} else {
+			1 + depth(padding[n as usize % 16] - 1)
+		}
+	}
+
+	#[test]
+	fn deep_recursion_on_big_stack() {
+		// far too deep for the 2 MiB test threads
+		assert_eq!(with_stack(Some(BIG_STACK), || depth(1_000_000)), 1_000_000);
+	}
+
+	#[test]
+	fn without_stack_size_runs_here() {
+		let here = thread::current().id();
+		assert!(with_stack(None, move || thread::current().id() == here));
+	}
+
+	#[test]
+	#[should_panic(expected = "from the solver")]
+	fn panics_are_passed_on() {
+		with_stack(Some(1 << 20), || panic!("from the solver"));
+	}
+
+	#[derive(KattisInput)]
+	struct Pair {
+		a: i64,
+		b: i64
+	}
+	impl TestCase for Pair {}
+
+	struct Sum(i64);
+	impl From<Cases<Pair>> for Sum {
+		fn from(pairs: Cases<Pair>) -> Self {
+			Sum(pairs.cases.iter().map(|pair| pair.a + pair.b).sum())
+		}
+	}
+	impl Display for Sum {
+		fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+			write!(f, "{}", self.0)
+		}
+	}
+
+	#[test]
+	fn solves_without_io() {
+		assert_eq!(solve::<Cases<Pair>, Sum>("2\n1 2\n3 4\n"), "10");
+	}
+
+	#[test]
+	fn profiles_every_phase() {
+		let (output, profile) = solve_profiled::<Cases<Pair>, Sum>("1\n1 2\n");
+		assert_eq!(output, "3");
+		let phases: Vec<_> = profile.phases.iter().map(|phase| phase.phase).collect();
+		assert_eq!(phases, vec![Phase::Parsing, Phase::Solving, Phase::Formatting]);
+	}
+
+	#[test]
+	#[should_panic(expected = "Cannot parse problem! line 3")]
+	fn parse_errors_have_position() {
+		solve::<Cases<Pair>, Sum>("2\n1 2\n");
+	}
+}
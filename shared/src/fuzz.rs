@@ -0,0 +1,302 @@
+//! A small mutational fuzzer for parsers.
+//! Starting from the sample inputs, it flips, inserts, deletes and duplicates bytes,
+//! swaps numbers for extreme ones, and checks that parsing never panics.
+//! Returning an `Err` is fine, that is the point: malformed input should give an error.
+//! A panicking input is minimized before it is reported, together with the seed,
+//! and `FUZZ_SEED=<seed> cargo test` runs the exact same inputs again.
+//! Without `FUZZ_SEED` the seed is always the same, so `cargo test` is too:
+//! set it to look at other inputs.
+
+use std::{
+	cell::Cell,
+	env,
+	panic::{self, AssertUnwindSafe},
+	str::FromStr,
+	sync::Once
+};
+
+use crate::random::Rng;
+
+pub const SEED_VARIABLE: &str = "FUZZ_SEED";
+/// The seed when `FUZZ_SEED` is not set.
+pub const DEFAULT_SEED: u64 = 0x5eed_f022;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+	pub iterations: usize,
+	pub seed:       u64
+}
+impl Default for Config {
+	/// 2000 iterations, seeded from `FUZZ_SEED` if it is set and `DEFAULT_SEED` otherwise.
+	fn default() -> Self {
+		Config {
+			iterations: 2000,
+			seed:       env::var(SEED_VARIABLE)
+				.ok()
+				.and_then(|seed| seed.parse().ok())
+				.unwrap_or(DEFAULT_SEED)
+		}
+	}
+}
+
+/// Fuzzes `P::from_str`, starting from the samples.
+#[track_caller]
+pub fn check_parser<P: FromStr>(samples: &[&str]) {
+	check_with(Config::default(), samples, |input| {
+		let _ = input.parse::<P>();
+	});
+}
+
+#[track_caller]
+pub fn check_with(config: Config, samples: &[&str], target: impl Fn(&str)) {
+	assert!(!samples.is_empty(), "The fuzzer needs at least one sample to start from!");
+	let mut rng = Rng::new(config.seed);
+	for iteration in 1..=config.iterations {
+		let mut bytes = rng.choose(samples).as_bytes().to_vec();
+		for _ in 0..rng.range(1..=4) {
+			mutate(&mut rng, &mut bytes);
+		}
+		// stdin is read into a String, so invalid UTF-8 never reaches a parser
+		let input = String::from_utf8_lossy(&bytes).into_owned();
+		if let Some(message) = panics(&target, &input) {
+			let minimal = minimize(&target, input.clone(), &message);
+			panic!(
+				"Parser panicked on iteration {} (seed {}): {}\n\
+				 minimized input ({} of {} bytes): {:?}\n\
+				 rerun with {}={}",
+				iteration,
+				config.seed,
+				message,
+				minimal.len(),
+				input.len(),
+				minimal,
+				SEED_VARIABLE,
+				config.seed
+			);
+		}
+	}
+}
+
+const INTERESTING: [&str; 10] = [
+	"0",
+	"-1",
+	"1",
+	"4294967296",
+	"99999999999999999999",
+	"\n",
+	" ",
+	"\r\n",
+	"\u{feff}",
+	"\u{e9}"
+];
+
+/// One random change to the bytes.
+pub fn mutate(rng: &mut Rng, bytes: &mut Vec<u8>) {
+	let position = |rng: &mut Rng, bytes: &Vec<u8>| rng.range(0..=bytes.len());
+	match rng.range(0..=6) {
+		// flip a bit
+		0 if !bytes.is_empty() => {
+			let index = rng.range(0..=bytes.len() - 1);
+			bytes[index] ^= 1 << rng.range(0..=7);
+		},
+		// insert something that tends to matter to a parser
+		1 => {
+			let at = position(rng, bytes);
+			let inserted = rng.choose(&INTERESTING).as_bytes();
+			bytes.splice(at..at, inserted.iter().copied());
+		},
+		// insert a random printable byte
+		2 => {
+			let at = position(rng, bytes);
+			bytes.insert(at, rng.range(b' '..=b'~'));
+		},
+		// delete a range
+		3 if !bytes.is_empty() => {
+			let start = rng.range(0..=bytes.len() - 1);
+			let end = rng.range(start + 1..=(start + 8).min(bytes.len()));
+			bytes.drain(start..end);
+		},
+		// duplicate a range, like an extra line
+		4 if !bytes.is_empty() => {
+			let start = rng.range(0..=bytes.len() - 1);
+			let end = rng.range(start + 1..=bytes.len());
+			let copy = bytes[start..end].to_vec();
+			let at = position(rng, bytes);
+			bytes.splice(at..at, copy);
+		},
+		// replace a number with an extreme one
+		5 => {
+			let numbers: Vec<(usize, usize)> = number_ranges(bytes);
+			if !numbers.is_empty() {
+				let (start, end) = *rng.choose(&numbers);
+				let replacement = rng.choose(&INTERESTING[..5]).as_bytes();
+				bytes.splice(start..end, replacement.iter().copied());
+			}
+		},
+		// cut off the end
+		_ => {
+			let at = position(rng, bytes);
+			bytes.truncate(at);
+		}
+	}
+}
+
+fn number_ranges(bytes: &[u8]) -> Vec<(usize, usize)> {
+	let mut ranges = Vec::new();
+	let mut start = None;
+	for (index, byte) in bytes.iter().enumerate() {
+		match (byte.is_ascii_digit(), start) {
+			(true, None) => start = Some(index),
+			(false, Some(begin)) => {
+				ranges.push((begin, index));
+				start = None;
+			},
+			_ => {}
+		}
+	}
+	if let Some(begin) = start {
+		ranges.push((begin, bytes.len()));
+	}
+	ranges
+}
+
+thread_local! {
+	static SILENT: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The panic message if the target panics on the input.
+/// The panic is not printed, as most inputs tried while minimizing panic.
+fn panics(target: &impl Fn(&str), input: &str) -> Option<String> {
+	static QUIET_HOOK: Once = Once::new();
+	QUIET_HOOK.call_once(|| {
+		let previous = panic::take_hook();
+		panic::set_hook(Box::new(move |info| {
+			if !SILENT.with(Cell::get) {
+				previous(info);
+			}
+		}));
+	});
+	SILENT.with(|silent| silent.set(true));
+	let result = panic::catch_unwind(AssertUnwindSafe(|| target(input)));
+	SILENT.with(|silent| silent.set(false));
+	let payload = result.err()?;
+	Some(match payload.downcast_ref::<&str>() {
+		Some(message) => message.to_string(),
+		None => match payload.downcast_ref::<String>() {
+			Some(message) => message.clone(),
+			None => "(no message)".to_string()
+		}
+	})
+}
+
+/// Removes chunks of characters, halving the chunk size whenever none can go,
+/// until no single character can be removed without the panic (with this message) going away.
+fn minimize(target: &impl Fn(&str), input: String, message: &str) -> String {
+	let mut characters: Vec<char> = input.chars().collect();
+	let mut chunk = characters.len().div_ceil(2).max(1);
+	loop {
+		let mut removed = false;
+		let mut start = 0;
+		while start < characters.len() {
+			let end = (start + chunk).min(characters.len());
+			let candidate: String = characters[..start]
+				.iter()
+				.chain(&characters[end..])
+				.collect();
+			if panics(target, &candidate).is_some_and(|new_message| new_message == message) {
+				characters.drain(start..end);
+				removed = true;
+			} else {
+				start += chunk;
+			}
+		}
+		if !removed {
+			if chunk == 1 {
+				break;
+			}
+			chunk = chunk.div_ceil(2);
+		}
+	}
+	characters.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::panic;
+
+	use crate::{
+		fuzz::{check_parser, check_with, minimize, mutate, Config},
+		random::Rng,
+		KattisInput
+	};
+
+	#[derive(Debug, KattisInput)]
+	struct Pair {
+		_a: u32,
+		_b: u32
+	}
+
+	#[derive(Debug, KattisInput)]
+	struct Board {
+		n:    usize,
+		// every row of a grid starts on a new line already
+		#[kattis(grid(rows = n, cols = n))]
+		grid: Vec<Vec<char>>
+	}
+
+	// so the fuzzing below also gets past the rejections
+	#[test]
+	fn board_parses_sample() {
+		let board: Board = include_str!("../../kattis/knightjump/src/1.in").parse().unwrap();
+		assert_eq!(board.grid.len(), board.n);
+		assert!(board.grid.iter().all(|row| row.len() == board.n));
+		assert_eq!(board.grid.iter().flatten().filter(|&&cell| cell == 'K').count(), 1);
+	}
+
+	#[test]
+	fn derived_parsers_never_panic() {
+		check_parser::<Pair>(&["1 2\n", "3 4"]);
+		check_parser::<Board>(&[include_str!("../../kattis/knightjump/src/1.in")]);
+	}
+
+	#[test]
+	fn mutations_change_input() {
+		let mut rng = Rng::new(5);
+		let changed = (0..100)
+			.filter(|_| {
+				let mut bytes = b"3\n1 2 3\n".to_vec();
+				mutate(&mut rng, &mut bytes);
+				bytes != b"3\n1 2 3\n"
+			})
+			.count();
+		assert!(changed > 50);
+	}
+
+	// panics on any `x` after the first line, like a parser with a stray unwrap
+	fn fragile(input: &str) {
+		if let Some((_, rest)) = input.split_once('\n') {
+			assert!(!rest.contains('x'), "found an x");
+		}
+	}
+
+	#[test]
+	fn minimizes_panicking_input() {
+		let minimal = minimize(&fragile, "12 34\n5 6 x 7\n8\n".to_string(), "found an x");
+		assert_eq!(minimal, "\nx");
+	}
+
+	#[test]
+	fn reports_seed_and_minimized_input() {
+		let config = Config {
+			iterations: 10_000,
+			seed:       99
+		};
+		let failure = panic::catch_unwind(|| {
+			check_with(config, &["1\n2 3 4\n"], fragile)
+		})
+		.unwrap_err();
+		let message = failure.downcast_ref::<String>().unwrap();
+		assert!(message.contains("seed 99") && message.contains("found an x"), "{}", message);
+		assert!(message.contains("\"\\nx\""), "{}", message);
+	}
+}
@@ -0,0 +1,232 @@
+//! Graph algorithms for the graph problems (wheresmyinternet, reachableroads, dominoes2 and so on).
+//! Nodes are numbered `0..n`, so inputs with ids from 1 have to be shifted first
+//! (`Graph::from_one_based` does that).
+//!
+//! [`Graph`] keeps unweighted edges compactly (compressed sparse rows: the neighbors of all nodes
+//! in one list, node after node), built once from the edges of an input:
+//! ```ignore
+//! let houses = Graph::from_one_based(n, Direction::Undirected, problem.connections);
+//! for &neighbor in houses.neighbors(0) { ... }
+//! ```
+//! - [`bridges`]: bridges, articulation points and 2-edge-connected components.
+//! - [`components`]: reachability and connected components.
+//! - [`dsu`]: disjoint sets, to connect nodes one edge at a time (and take it back).
+//! - [`scc`]: strongly connected components, and the graph between them.
+//! - [`topo`]: topological orders, cycles, and dynamic programming along the order.
+//! - [`weighted`]: a `Graph` with a weight per edge, and shortest paths with Dijkstra, 0-1 BFS
+//!   and Bellman-Ford.
+
+pub mod bridges;
+pub mod components;
+pub mod dsu;
+pub mod scc;
+pub mod topo;
+pub mod weighted;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	Directed,
+	/// Every edge goes both ways.
+	Undirected
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Graph {
+	direction: Direction,
+	// the neighbors of `node` are `targets[offsets[node]..offsets[node + 1]]`
+	offsets:   Vec<usize>,
+	targets:   Vec<usize>,
+	// the edge every target was reached through, by its index in the input
+	edge_ids:  Vec<usize>,
+	endpoints: Vec<(usize, usize)>
+}
+
+impl Graph {
+	/// A graph of `nodes` nodes numbered from 0, with the edges `(from, to)`.
+	/// In an undirected graph every edge is a neighbor of both ends, but a self loop only once.
+	/// Panics on a node that is not in the graph.
+	pub fn from_edges(
+		nodes: usize,
+		direction: Direction,
+		edges: impl IntoIterator<Item = (usize, usize)>
+	) -> Self {
+		let endpoints: Vec<(usize, usize)> = edges.into_iter().collect();
+		let mut offsets = vec![0; nodes + 1];
+		for &(from, to) in endpoints.iter() {
+			assert!(
+				from < nodes && to < nodes,
+				"edge {} -> {} in a graph of {} nodes",
+				from,
+				to,
+				nodes
+			);
+			offsets[from + 1] += 1;
+			if direction == Direction::Undirected && from != to {
+				offsets[to + 1] += 1;
+			}
+		}
+		for node in 0..nodes {
+			offsets[node + 1] += offsets[node];
+		}
+		// fill every row from its start, in the order of the edges
+		let mut next = offsets.clone();
+		let mut targets = vec![0; offsets[nodes]];
+		let mut edge_ids = vec![0; offsets[nodes]];
+		let mut add = |from: usize, to: usize, id: usize| {
+			targets[next[from]] = to;
+			edge_ids[next[from]] = id;
+			next[from] += 1;
+		};
+		for (id, &(from, to)) in endpoints.iter().enumerate() {
+			add(from, to, id);
+			if direction == Direction::Undirected && from != to {
+				add(to, from, id);
+			}
+		}
+		Graph {
+			direction,
+			offsets,
+			targets,
+			edge_ids,
+			endpoints
+		}
+	}
+
+	/// Like `from_edges`, for inputs that number the nodes `1..=nodes`.
+	/// Node `i` of the input is node `i - 1` of the graph.
+	pub fn from_one_based(
+		nodes: usize,
+		direction: Direction,
+		edges: impl IntoIterator<Item = (usize, usize)>
+	) -> Self {
+		let shift = |node: usize| node.checked_sub(1).expect("node 0 in a graph numbered from 1");
+		Graph::from_edges(nodes, direction, edges.into_iter().map(|(a, b)| (shift(a), shift(b))))
+	}
+
+	pub fn direction(&self) -> Direction {
+		self.direction
+	}
+
+	pub fn nodes(&self) -> usize {
+		self.offsets.len() - 1
+	}
+
+	/// The number of edges it was built from, an undirected edge counts once.
+	pub fn edge_count(&self) -> usize {
+		self.endpoints.len()
+	}
+
+	/// The ends of an edge by its index in the input, as given.
+	pub fn endpoints(&self, edge: usize) -> (usize, usize) {
+		self.endpoints[edge]
+	}
+
+	/// The nodes an edge from `node` goes to, once per edge (so maybe more than once).
+	pub fn neighbors(&self, node: usize) -> &[usize] {
+		&self.targets[self.offsets[node]..self.offsets[node + 1]]
+	}
+
+	/// The edges from `node` as their target and index in the input, in the order of `neighbors`.
+	pub fn edges(&self, node: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		let range = self.offsets[node]..self.offsets[node + 1];
+		self.targets[range.clone()]
+			.iter()
+			.copied()
+			.zip(self.edge_ids[range].iter().copied())
+	}
+
+	/// The number of edges from `node`.
+	pub fn degree(&self, node: usize) -> usize {
+		self.offsets[node + 1] - self.offsets[node]
+	}
+
+	/// Every node with its neighbors, like `Dot::from_adjacency` takes.
+	pub fn adjacency(&self) -> impl Iterator<Item = (usize, &[usize])> {
+		(0..self.nodes()).map(|node| (node, self.neighbors(node)))
+	}
+
+	/// The same graph with every edge turned around, with the same edge indices.
+	/// An undirected graph stays the same.
+	pub fn reversed(&self) -> Graph {
+		match self.direction {
+			Direction::Directed => Graph::from_edges(
+				self.nodes(),
+				Direction::Directed,
+				self.endpoints.iter().map(|&(from, to)| (to, from))
+			),
+			Direction::Undirected => self.clone()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn directed() {
+		let graph = Graph::from_edges(4, Direction::Directed, [(0, 1), (2, 1), (0, 3), (0, 1)]);
+		assert_eq!(graph.nodes(), 4);
+		assert_eq!(graph.edge_count(), 4);
+		assert_eq!(graph.neighbors(0), &[1, 3, 1]);
+		assert_eq!(graph.neighbors(1), &[] as &[usize]);
+		assert_eq!(graph.edges(0).collect::<Vec<_>>(), vec![(1, 0), (3, 2), (1, 3)]);
+		assert_eq!(graph.degree(2), 1);
+		let reversed = graph.reversed();
+		assert_eq!(reversed.neighbors(1), &[0, 2, 0]);
+		assert_eq!(reversed.edges(1).map(|(_, id)| id).collect::<Vec<_>>(), vec![0, 1, 3]);
+		assert_eq!(reversed.endpoints(1), (1, 2));
+	}
+
+	#[test]
+	fn undirected_one_based() {
+		// houses numbered from 1 like in wheresmyinternet, with a self loop
+		let edges = [(1, 2), (2, 3), (3, 4), (5, 6), (5, 5)];
+		let graph = Graph::from_one_based(6, Direction::Undirected, edges);
+		assert_eq!(graph.neighbors(1), &[0, 2]);
+		assert_eq!(graph.neighbors(4), &[5, 4]);
+		assert_eq!(graph.degree(4), 2);
+		assert_eq!(graph.endpoints(0), (0, 1));
+		assert_eq!(graph.reversed(), graph);
+		assert_eq!(graph.adjacency().map(|(_, neighbors)| neighbors.len()).sum::<usize>(), 9);
+	}
+
+	#[test]
+	#[should_panic(expected = "node 0 in a graph numbered from 1")]
+	fn zero_in_one_based() {
+		Graph::from_one_based(2, Direction::Undirected, [(0, 1)]);
+	}
+
+	#[test]
+	fn matches_adjacency_lists() {
+		const NODES: usize = 10;
+		property::check(
+			|rng| {
+				let node = |rng: &mut Rng| rng.range(0..=NODES - 1);
+				let edges = rng.vec(0..=30, |rng| (node(rng), node(rng)));
+				(edges, rng.chance(0.5))
+			},
+			|(edges, directed)| {
+				let direction = if *directed { Direction::Directed } else { Direction::Undirected };
+				let graph = Graph::from_edges(NODES, direction, edges.iter().copied());
+				let mut lists = vec![Vec::new(); NODES];
+				for &(from, to) in edges.iter() {
+					lists[from].push(to);
+					if !directed && from != to {
+						lists[to].push(from);
+					}
+				}
+				for (node, list) in lists.iter().enumerate() {
+					ensure(graph.neighbors(node) == list, || {
+						format!("{} has {:?}, not {:?}", node, graph.neighbors(node), list)
+					})?;
+				}
+				Ok(())
+			}
+		);
+	}
+}
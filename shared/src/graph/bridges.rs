@@ -0,0 +1,259 @@
+//! The weak spots of an undirected graph: edges (bridges) and nodes (articulation points) that
+//! disconnect it when they are cut, found with Tarjan's low links and no recursion.
+//!
+//! ```ignore
+//! let houses = Graph::from_one_based(n, Direction::Undirected, connections);
+//! let cuts = houses.cuts();
+//! for &cable in cuts.bridges() {
+//!     let (a, b) = houses.endpoints(cable); // houses on one side lose the other side
+//! }
+//! ```
+//! Edges are told apart by their index, so two cables between the same houses are no bridge,
+//! and a self loop never is one.
+
+use crate::graph::{Direction, Graph};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cuts {
+	bridges:             Vec<usize>,
+	articulation_points: Vec<usize>,
+	// the 2-edge-connected component of every node, numbered in the order of their smallest node
+	components:          Vec<usize>,
+	count:               usize
+}
+
+impl Graph {
+	/// The bridges, articulation points and 2-edge-connected components (what stays connected
+	/// whichever single edge is cut) of an undirected graph.
+	pub fn cuts(&self) -> Cuts {
+		assert!(
+			self.direction() == Direction::Undirected,
+			"bridges are only for undirected graphs"
+		);
+		let nodes = self.nodes();
+		let mut order = vec![usize::MAX; nodes];
+		// the earliest node it can get back to without the edge it was reached through
+		let mut low = vec![0; nodes];
+		let mut is_bridge = vec![false; self.edge_count()];
+		let mut is_articulation_point = vec![false; nodes];
+		let mut seen = 0;
+		// the nodes being visited, with the edge they were reached through
+		// and how many of their edges were looked at
+		let mut calls: Vec<(usize, usize, usize)> = Vec::new();
+		for root in 0..nodes {
+			if order[root] != usize::MAX {
+				continue;
+			}
+			order[root] = seen;
+			low[root] = seen;
+			seen += 1;
+			calls.push((root, usize::MAX, 0));
+			let mut root_children = 0;
+			while let Some(&(node, through, position)) = calls.last() {
+				let edge = self.offsets[node] + position;
+				if edge < self.offsets[node + 1] {
+					calls.last_mut().unwrap().2 += 1;
+					let (next, id) = (self.targets[edge], self.edge_ids[edge]);
+					if id == through {
+						continue;
+					}
+					if order[next] == usize::MAX {
+						order[next] = seen;
+						low[next] = seen;
+						seen += 1;
+						calls.push((next, id, 0));
+					} else {
+						low[node] = low[node].min(order[next]);
+					}
+					continue;
+				}
+				calls.pop();
+				let Some(&(parent, _, _)) = calls.last() else {
+					continue;
+				};
+				low[parent] = low[parent].min(low[node]);
+				// nothing below `node` gets back above it (or to the parent) another way
+				if low[node] > order[parent] {
+					is_bridge[through] = true;
+				}
+				if parent == root {
+					root_children += 1;
+				} else if low[node] >= order[parent] {
+					is_articulation_point[parent] = true;
+				}
+			}
+			is_articulation_point[root] = root_children > 1;
+		}
+
+		// what is still connected without the bridges
+		let mut components = vec![usize::MAX; nodes];
+		let mut count = 0;
+		let mut stack = Vec::new();
+		for start in 0..nodes {
+			if components[start] != usize::MAX {
+				continue;
+			}
+			components[start] = count;
+			stack.push(start);
+			while let Some(node) = stack.pop() {
+				for (next, edge) in self.edges(node) {
+					if !is_bridge[edge] && components[next] == usize::MAX {
+						components[next] = count;
+						stack.push(next);
+					}
+				}
+			}
+			count += 1;
+		}
+		let which = |flags: Vec<bool>| (0..flags.len()).filter(|&index| flags[index]).collect();
+		Cuts {
+			bridges: which(is_bridge),
+			articulation_points: which(is_articulation_point),
+			components,
+			count
+		}
+	}
+}
+
+impl Cuts {
+	/// The edges that disconnect the graph when cut, by their index in the input, in order.
+	pub fn bridges(&self) -> &[usize] {
+		&self.bridges
+	}
+
+	pub fn is_bridge(&self, edge: usize) -> bool {
+		self.bridges.binary_search(&edge).is_ok()
+	}
+
+	/// The nodes that disconnect the rest of the graph when cut out, in order.
+	pub fn articulation_points(&self) -> &[usize] {
+		&self.articulation_points
+	}
+
+	pub fn is_articulation_point(&self, node: usize) -> bool {
+		self.articulation_points.binary_search(&node).is_ok()
+	}
+
+	/// The number of 2-edge-connected components.
+	pub fn count(&self) -> usize {
+		self.count
+	}
+
+	/// The 2-edge-connected component of a node.
+	pub fn component(&self, node: usize) -> usize {
+		self.components[node]
+	}
+
+	/// The 2-edge-connected component of every node.
+	pub fn components(&self) -> &[usize] {
+		&self.components
+	}
+
+	/// The nodes of every 2-edge-connected component, in increasing order.
+	pub fn members(&self) -> Vec<Vec<usize>> {
+		let mut members = vec![Vec::new(); self.count];
+		for (node, &component) in self.components.iter().enumerate() {
+			members[component].push(node);
+		}
+		members
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn houses() {
+		// a triangle 0 1 2, then 2 - 3 - 4, with 4 = 5 twice and a loop on 5, and 6 alone
+		let edges = [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 5), (5, 4), (5, 5)];
+		let graph = Graph::from_edges(7, Direction::Undirected, edges);
+		let cuts = graph.cuts();
+		assert_eq!(cuts.bridges(), &[3, 4]);
+		assert!(cuts.is_bridge(3) && !cuts.is_bridge(5) && !cuts.is_bridge(7));
+		assert_eq!(cuts.articulation_points(), &[2, 3, 4]);
+		assert!(cuts.is_articulation_point(4) && !cuts.is_articulation_point(5));
+		assert_eq!(cuts.count(), 4);
+		assert_eq!(cuts.members(), vec![vec![0, 1, 2], vec![3], vec![4, 5], vec![6]]);
+		assert_eq!(cuts.components(), &[0, 0, 0, 1, 2, 2, 3]);
+		assert_eq!(cuts.component(5), 2);
+	}
+
+	#[test]
+	fn star() {
+		// the root of the search is an articulation point only with more than one child
+		let graph = Graph::from_edges(4, Direction::Undirected, [(0, 1), (0, 2), (0, 3)]);
+		assert_eq!(graph.cuts().articulation_points(), &[0]);
+		let graph = Graph::from_edges(3, Direction::Undirected, [(0, 1), (1, 2)]);
+		assert_eq!(graph.cuts().articulation_points(), &[1]);
+	}
+
+	#[test]
+	fn long_chain_and_cycle() {
+		let nodes = 200_000;
+		let chain = Graph::from_edges(nodes, Direction::Undirected, (1..nodes).map(|n| (n - 1, n)));
+		let cuts = chain.cuts();
+		assert_eq!(cuts.bridges().len(), nodes - 1);
+		assert_eq!(cuts.articulation_points().len(), nodes - 2);
+		let around = (0..nodes).map(|n| (n, (n + 1) % nodes));
+		let cycle = Graph::from_edges(nodes, Direction::Undirected, around);
+		let cuts = cycle.cuts();
+		assert!(cuts.bridges().is_empty() && cuts.articulation_points().is_empty());
+		assert_eq!(cuts.count(), 1);
+	}
+
+	// cut every edge and every node in turn, and see whether more pieces are left
+	#[test]
+	fn matches_cutting() {
+		const NODES: usize = 8;
+		property::check(
+			|rng| {
+				let node = |rng: &mut Rng| rng.range(0..=NODES - 1);
+				rng.vec(0..=12, |rng| (node(rng), node(rng)))
+			},
+			|edges| {
+				let pieces = |keep: &dyn Fn(usize, (usize, usize)) -> bool| {
+					let kept = edges.iter().enumerate().filter(|&(id, &edge)| keep(id, edge));
+					let kept = kept.map(|(_, &edge)| edge);
+					Graph::from_edges(NODES, Direction::Undirected, kept).components()
+				};
+				let graph = Graph::from_edges(NODES, Direction::Undirected, edges.iter().copied());
+				let cuts = graph.cuts();
+				let whole = pieces(&|_, _| true);
+				for (cut, edge) in edges.iter().enumerate() {
+					let without = pieces(&|id, _| id != cut);
+					let bridge = without.count() > whole.count();
+					ensure(cuts.is_bridge(cut) == bridge, || {
+						format!("edge {} {:?} is a bridge: {}", cut, edge, bridge)
+					})?;
+				}
+				for cut in 0..NODES {
+					// the node itself is left on its own
+					let without = pieces(&|_, (a, b)| a != cut && b != cut);
+					let articulation = without.count() > whole.count() + 1;
+					ensure(cuts.is_articulation_point(cut) == articulation, || {
+						format!("node {} is an articulation point: {}", cut, articulation)
+					})?;
+				}
+				// in the same component when no single cut separates them
+				for a in 0..NODES {
+					for b in 0..NODES {
+						let together = whole.component(a) == whole.component(b)
+							&& (0..edges.len()).all(|cut| {
+								let without = pieces(&|id, _| id != cut);
+								without.component(a) == without.component(b)
+							});
+						ensure((cuts.component(a) == cuts.component(b)) == together, || {
+							format!("{} and {} together: {}", a, b, together)
+						})?;
+					}
+				}
+				Ok(())
+			}
+		);
+	}
+}
@@ -0,0 +1,177 @@
+//! Which nodes can reach which, without recursion (so a chain of 200 000 houses is fine).
+//!
+//! ```ignore
+//! let houses = Graph::from_one_based(n, Direction::Undirected, connections);
+//! let connected = houses.reachable_from(0); // house 1
+//! let missing_roads = city.components().count() - 1;
+//! ```
+
+use crate::graph::{Direction, Graph};
+
+/// The connected components of a graph, numbered in the order of their smallest node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Components {
+	labels:          Vec<usize>,
+	sizes:           Vec<usize>,
+	representatives: Vec<usize>
+}
+
+impl Graph {
+	/// For every node, whether it can be reached from `source` (which can reach itself).
+	pub fn reachable_from(&self, source: usize) -> Vec<bool> {
+		let mut reached = vec![false; self.nodes()];
+		reached[source] = true;
+		let mut stack = vec![source];
+		while let Some(node) = stack.pop() {
+			for &next in self.neighbors(node) {
+				if !reached[next] {
+					reached[next] = true;
+					stack.push(next);
+				}
+			}
+		}
+		reached
+	}
+
+	/// The connected components, where a directed edge connects both ways
+	/// (the weakly connected components of a directed graph).
+	pub fn components(&self) -> Components {
+		let reversed = match self.direction() {
+			Direction::Directed => Some(self.reversed()),
+			Direction::Undirected => None
+		};
+		let mut components = Components {
+			labels:          vec![usize::MAX; self.nodes()],
+			sizes:           Vec::new(),
+			representatives: Vec::new()
+		};
+		let mut stack = Vec::new();
+		for start in 0..self.nodes() {
+			if components.labels[start] != usize::MAX {
+				continue;
+			}
+			let label = components.sizes.len();
+			components.labels[start] = label;
+			components.sizes.push(1);
+			components.representatives.push(start);
+			stack.push(start);
+			while let Some(node) = stack.pop() {
+				let backwards =
+					reversed.as_ref().map_or(&[][..], |reversed| reversed.neighbors(node));
+				for &next in self.neighbors(node).iter().chain(backwards) {
+					if components.labels[next] == usize::MAX {
+						components.labels[next] = label;
+						components.sizes[label] += 1;
+						stack.push(next);
+					}
+				}
+			}
+		}
+		components
+	}
+}
+
+impl Components {
+	pub fn count(&self) -> usize {
+		self.sizes.len()
+	}
+
+	/// The component of a node.
+	pub fn component(&self, node: usize) -> usize {
+		self.labels[node]
+	}
+
+	/// The component of every node.
+	pub fn labels(&self) -> &[usize] {
+		&self.labels
+	}
+
+	pub fn size(&self, component: usize) -> usize {
+		self.sizes[component]
+	}
+
+	pub fn sizes(&self) -> &[usize] {
+		&self.sizes
+	}
+
+	/// The smallest node of every component.
+	pub fn representatives(&self) -> &[usize] {
+		&self.representatives
+	}
+
+	/// The nodes of every component, in increasing order.
+	pub fn members(&self) -> Vec<Vec<usize>> {
+		let mut members: Vec<Vec<usize>> =
+			self.sizes.iter().map(|&size| Vec::with_capacity(size)).collect();
+		for (node, &label) in self.labels.iter().enumerate() {
+			members[label].push(node);
+		}
+		members
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn houses() {
+		// 1 - 2 - 3, 4 - 5 and 6 alone, numbered from 1
+		let graph = Graph::from_one_based(6, Direction::Undirected, [(1, 2), (3, 2), (5, 4)]);
+		assert_eq!(graph.reachable_from(0), vec![true, true, true, false, false, false]);
+		let components = graph.components();
+		assert_eq!(components.count(), 3);
+		assert_eq!(components.labels(), &[0, 0, 0, 1, 1, 2]);
+		assert_eq!(components.sizes(), &[3, 2, 1]);
+		assert_eq!(components.representatives(), &[0, 3, 5]);
+		assert_eq!(components.members(), vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+		assert_eq!((components.component(4), components.size(1)), (1, 2));
+	}
+
+	#[test]
+	fn directed_edges() {
+		let graph = Graph::from_edges(4, Direction::Directed, [(1, 0), (1, 2), (3, 3)]);
+		assert_eq!(graph.reachable_from(0), vec![true, false, false, false]);
+		assert_eq!(graph.reachable_from(1), vec![true, true, true, false]);
+		assert_eq!(graph.components().members(), vec![vec![0, 1, 2], vec![3]]);
+	}
+
+	#[test]
+	fn long_chain() {
+		let nodes = 200_000;
+		let graph = Graph::from_edges(nodes, Direction::Undirected, (1..nodes).map(|n| (n - 1, n)));
+		assert!(graph.reachable_from(nodes - 1).into_iter().all(|reached| reached));
+		assert_eq!(graph.components().count(), 1);
+	}
+
+	#[test]
+	fn same_component_when_reachable() {
+		const NODES: usize = 12;
+		property::check(
+			|rng| {
+				let node = |rng: &mut Rng| rng.range(0..=NODES - 1);
+				rng.vec(0..=12, |rng| (node(rng), node(rng)))
+			},
+			|edges| {
+				let graph = Graph::from_edges(NODES, Direction::Undirected, edges.iter().copied());
+				let components = graph.components();
+				for a in 0..NODES {
+					let reached = graph.reachable_from(a);
+					for (b, &reached) in reached.iter().enumerate() {
+						let same = components.component(a) == components.component(b);
+						ensure(same == reached, || {
+							format!("{} and {}: same component {}, reached {}", a, b, same, reached)
+						})?;
+					}
+				}
+				ensure(components.sizes().iter().sum::<usize>() == NODES, || {
+					format!("sizes {:?}", components.sizes())
+				})
+			}
+		);
+	}
+}
@@ -0,0 +1,298 @@
+//! Disjoint sets (union-find): which nodes are connected so far, while edges keep being added.
+//!
+//! ```ignore
+//! let mut endpoints = Dsu::new(n);
+//! for (a, b) in roads {
+//!     endpoints.union(a, b);
+//! }
+//! let missing_roads = endpoints.count_sets() - 1;
+//! ```
+//! `RollbackDsu` can also take back the last unions, for answering connectivity questions
+//! offline while edges come and go.
+
+/// With path compression and union by size, so every operation is close to constant time.
+#[derive(Debug, Clone)]
+pub struct Dsu {
+	parents: Vec<usize>,
+	sizes:   Vec<usize>, // only right for the roots
+	sets:    usize
+}
+
+impl Dsu {
+	/// `n` sets of one element each.
+	pub fn new(n: usize) -> Self {
+		Dsu {
+			parents: (0..n).collect(),
+			sizes:   vec![1; n],
+			sets:    n
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.parents.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.parents.is_empty()
+	}
+
+	/// The element that stands for the set of `x`, the same for everything in it.
+	pub fn find(&mut self, x: usize) -> usize {
+		let mut root = x;
+		while self.parents[root] != root {
+			root = self.parents[root];
+		}
+		// point everything on the way straight at the root
+		let mut node = x;
+		while self.parents[node] != root {
+			node = std::mem::replace(&mut self.parents[node], root);
+		}
+		root
+	}
+
+	/// Joins the sets of `a` and `b`, `false` if they already were one.
+	pub fn union(&mut self, a: usize, b: usize) -> bool {
+		let (mut a, mut b) = (self.find(a), self.find(b));
+		if a == b {
+			return false;
+		}
+		if self.sizes[a] < self.sizes[b] {
+			std::mem::swap(&mut a, &mut b);
+		}
+		self.parents[b] = a;
+		self.sizes[a] += self.sizes[b];
+		self.sets -= 1;
+		true
+	}
+
+	pub fn same(&mut self, a: usize, b: usize) -> bool {
+		self.find(a) == self.find(b)
+	}
+
+	/// The number of elements in the set of `x`.
+	pub fn size(&mut self, x: usize) -> usize {
+		let root = self.find(x);
+		self.sizes[root]
+	}
+
+	pub fn count_sets(&self) -> usize {
+		self.sets
+	}
+
+	/// The elements of every set, in increasing order, sets ordered by their smallest element.
+	pub fn groups(&mut self) -> Vec<Vec<usize>> {
+		let mut group_of_root = vec![usize::MAX; self.len()];
+		let mut groups: Vec<Vec<usize>> = Vec::with_capacity(self.sets);
+		for x in 0..self.len() {
+			let root = self.find(x);
+			if group_of_root[root] == usize::MAX {
+				group_of_root[root] = groups.len();
+				groups.push(Vec::new());
+			}
+			groups[group_of_root[root]].push(x);
+		}
+		groups
+	}
+}
+
+/// Like `Dsu`, but every union can be taken back. Without path compression, so `find` is
+/// logarithmic instead.
+#[derive(Debug, Clone)]
+pub struct RollbackDsu {
+	parents: Vec<usize>,
+	sizes:   Vec<usize>,
+	sets:    usize,
+	// the root that got attached to another by every union, in order
+	history: Vec<usize>
+}
+
+impl RollbackDsu {
+	pub fn new(n: usize) -> Self {
+		RollbackDsu {
+			parents: (0..n).collect(),
+			sizes:   vec![1; n],
+			sets:    n,
+			history: Vec::new()
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.parents.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.parents.is_empty()
+	}
+
+	pub fn find(&self, mut x: usize) -> usize {
+		while self.parents[x] != x {
+			x = self.parents[x];
+		}
+		x
+	}
+
+	/// Joins the sets of `a` and `b`, `false` if they already were one.
+	/// Only unions that joined something are taken back by `rollback`.
+	pub fn union(&mut self, a: usize, b: usize) -> bool {
+		let (mut a, mut b) = (self.find(a), self.find(b));
+		if a == b {
+			return false;
+		}
+		if self.sizes[a] < self.sizes[b] {
+			std::mem::swap(&mut a, &mut b);
+		}
+		self.parents[b] = a;
+		self.sizes[a] += self.sizes[b];
+		self.sets -= 1;
+		self.history.push(b);
+		true
+	}
+
+	pub fn same(&self, a: usize, b: usize) -> bool {
+		self.find(a) == self.find(b)
+	}
+
+	pub fn size(&self, x: usize) -> usize {
+		self.sizes[self.find(x)]
+	}
+
+	pub fn count_sets(&self) -> usize {
+		self.sets
+	}
+
+	/// The state to come back to with `rollback`.
+	pub fn snapshot(&self) -> usize {
+		self.history.len()
+	}
+
+	/// Takes back every union since the snapshot, last one first.
+	pub fn rollback(&mut self, snapshot: usize) {
+		assert!(snapshot <= self.history.len(), "snapshot {} is from the future", snapshot);
+		while self.history.len() > snapshot {
+			let child = self.history.pop().unwrap();
+			let root = self.parents[child];
+			self.sizes[root] -= self.sizes[child];
+			self.parents[child] = child;
+			self.sets += 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{
+			dsu::{Dsu, RollbackDsu},
+			Direction, Graph
+		},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn unions() {
+		let mut dsu = Dsu::new(6);
+		assert!(dsu.union(0, 1));
+		assert!(dsu.union(3, 4));
+		assert!(dsu.union(1, 2));
+		assert!(!dsu.union(2, 0));
+		assert!(dsu.same(0, 2));
+		assert!(!dsu.same(0, 3));
+		assert_eq!((dsu.size(2), dsu.size(4), dsu.size(5)), (3, 2, 1));
+		assert_eq!(dsu.count_sets(), 3);
+		assert_eq!(dsu.groups(), vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+		assert_eq!((dsu.len(), Dsu::new(0).is_empty()), (6, true));
+	}
+
+	#[test]
+	fn rollback() {
+		let mut dsu = RollbackDsu::new(5);
+		dsu.union(0, 1);
+		let snapshot = dsu.snapshot();
+		dsu.union(2, 3);
+		dsu.union(1, 3);
+		assert!(!dsu.union(0, 2));
+		assert_eq!((dsu.size(0), dsu.count_sets()), (4, 2));
+		dsu.rollback(snapshot);
+		assert!(dsu.same(0, 1));
+		assert!(!dsu.same(1, 3));
+		assert!(!dsu.same(2, 3));
+		assert_eq!((dsu.size(0), dsu.size(2), dsu.count_sets()), (2, 1, 4));
+		dsu.rollback(0);
+		assert_eq!(dsu.count_sets(), 5);
+	}
+
+	// random edges, with the components of the graph they make as the reference
+	fn edges(rng: &mut Rng) -> (usize, Vec<(usize, usize)>) {
+		let nodes = rng.range(1..=15);
+		let node = |rng: &mut Rng| rng.range(0..=nodes - 1);
+		(nodes, rng.vec(0..=20, |rng| (node(rng), node(rng))))
+	}
+
+	#[test]
+	fn matches_components() {
+		property::check(
+			edges,
+			|(nodes, edges)| {
+				let mut dsu = Dsu::new(*nodes);
+				for &(a, b) in edges.iter().filter(|&&(a, b)| a < *nodes && b < *nodes) {
+					dsu.union(a, b);
+				}
+				let graph = Graph::from_edges(
+					*nodes,
+					Direction::Undirected,
+					edges.iter().copied().filter(|&(a, b)| a < *nodes && b < *nodes)
+				);
+				let components = graph.components();
+				ensure(dsu.groups() == components.members(), || {
+					format!("{:?} instead of {:?}", dsu.groups(), components.members())
+				})?;
+				for node in 0..*nodes {
+					let size = components.size(components.component(node));
+					ensure(dsu.size(node) == size, || format!("size of {}", node))?;
+				}
+				ensure(dsu.count_sets() == components.count(), || "count".to_string())
+			}
+		);
+	}
+
+	#[test]
+	fn rollback_matches_rebuilding() {
+		property::check(
+			|rng| {
+				let (nodes, edges) = edges(rng);
+				let keep = rng.range(0..=edges.len());
+				(nodes, edges, keep)
+			},
+			|(nodes, edges, keep)| {
+				let edges: Vec<_> =
+					edges.iter().copied().filter(|&(a, b)| a < *nodes && b < *nodes).collect();
+				let keep = (*keep).min(edges.len());
+				let mut dsu = RollbackDsu::new(*nodes);
+				let mut snapshot = 0;
+				for (index, &(a, b)) in edges.iter().enumerate() {
+					if index == keep {
+						snapshot = dsu.snapshot();
+					}
+					dsu.union(a, b);
+				}
+				if keep == edges.len() {
+					snapshot = dsu.snapshot();
+				}
+				dsu.rollback(snapshot);
+				let mut rebuilt = Dsu::new(*nodes);
+				for &(a, b) in edges[..keep].iter() {
+					rebuilt.union(a, b);
+				}
+				for a in 0..*nodes {
+					for b in 0..*nodes {
+						let same = rebuilt.same(a, b);
+						ensure(dsu.same(a, b) == same, || format!("{} and {}", a, b))?;
+					}
+					ensure(dsu.size(a) == rebuilt.size(a), || format!("size of {}", a))?;
+				}
+				ensure(dsu.count_sets() == rebuilt.count_sets(), || "count".to_string())
+			}
+		);
+	}
+}
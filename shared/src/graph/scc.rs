@@ -0,0 +1,235 @@
+//! Strongly connected components: groups of nodes that can all reach each other,
+//! like dominoes that knock each other over in a circle.
+//!
+//! ```ignore
+//! let lineups = Graph::from_one_based(n, Direction::Directed, knocks_over);
+//! let scc = lineups.strongly_connected();
+//! // pushing one domino of every component nothing else knocks over topples everything
+//! let fewest_pushes = scc.sources().len();
+//! ```
+
+use crate::graph::{Direction, Graph};
+
+/// The components are numbered in topological order: edges between components always go from a
+/// lower number to a higher one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StronglyConnected {
+	components:   Vec<usize>,
+	condensation: Graph,
+	in_degrees:   Vec<usize>
+}
+
+// the state of Tarjan's algorithm while it goes
+struct Tarjan {
+	order:    Vec<usize>, // when every node was first seen
+	low:      Vec<usize>, // the earliest node still on the stack it can get back to
+	on_stack: Vec<bool>,
+	stack:    Vec<usize>,
+	// the nodes being visited, with how many of their neighbors were looked at
+	calls:    Vec<(usize, usize)>,
+	seen:     usize
+}
+
+impl Tarjan {
+	fn visit(&mut self, node: usize) {
+		self.order[node] = self.seen;
+		self.low[node] = self.seen;
+		self.seen += 1;
+		self.stack.push(node);
+		self.on_stack[node] = true;
+		self.calls.push((node, 0));
+	}
+}
+
+impl Graph {
+	/// Tarjan's algorithm, with its own stack instead of recursion.
+	pub fn strongly_connected(&self) -> StronglyConnected {
+		let nodes = self.nodes();
+		let mut tarjan = Tarjan {
+			order:    vec![usize::MAX; nodes],
+			low:      vec![0; nodes],
+			on_stack: vec![false; nodes],
+			stack:    Vec::new(),
+			calls:    Vec::new(),
+			seen:     0
+		};
+		let mut components = vec![usize::MAX; nodes];
+		let mut count = 0;
+		for start in 0..nodes {
+			if tarjan.order[start] != usize::MAX {
+				continue;
+			}
+			tarjan.visit(start);
+			while let Some(&(node, position)) = tarjan.calls.last() {
+				if let Some(&next) = self.neighbors(node).get(position) {
+					tarjan.calls.last_mut().unwrap().1 += 1;
+					if tarjan.order[next] == usize::MAX {
+						tarjan.visit(next);
+					} else if tarjan.on_stack[next] {
+						tarjan.low[node] = tarjan.low[node].min(tarjan.order[next]);
+					}
+					continue;
+				}
+				tarjan.calls.pop();
+				if let Some(&(parent, _)) = tarjan.calls.last() {
+					tarjan.low[parent] = tarjan.low[parent].min(tarjan.low[node]);
+				}
+				if tarjan.low[node] == tarjan.order[node] {
+					// everything above it on the stack is its component
+					loop {
+						let member = tarjan.stack.pop().unwrap();
+						tarjan.on_stack[member] = false;
+						components[member] = count;
+						if member == node {
+							break;
+						}
+					}
+					count += 1;
+				}
+			}
+		}
+		// Tarjan finds a component only after all components it reaches, so the other way around
+		for component in components.iter_mut() {
+			*component = count - 1 - *component;
+		}
+
+		let mut edges: Vec<(usize, usize)> = (0..nodes)
+			.flat_map(|node| self.neighbors(node).iter().map(move |&next| (node, next)))
+			.map(|(node, next)| (components[node], components[next]))
+			.filter(|(from, to)| from != to)
+			.collect();
+		edges.sort_unstable();
+		edges.dedup();
+		let mut in_degrees = vec![0; count];
+		for &(_, to) in edges.iter() {
+			in_degrees[to] += 1;
+		}
+		StronglyConnected {
+			components,
+			condensation: Graph::from_edges(count, Direction::Directed, edges),
+			in_degrees
+		}
+	}
+}
+
+impl StronglyConnected {
+	pub fn count(&self) -> usize {
+		self.in_degrees.len()
+	}
+
+	/// The component of a node.
+	pub fn component(&self, node: usize) -> usize {
+		self.components[node]
+	}
+
+	/// The component of every node.
+	pub fn components(&self) -> &[usize] {
+		&self.components
+	}
+
+	/// The nodes of every component, in increasing order.
+	pub fn members(&self) -> Vec<Vec<usize>> {
+		let mut members = vec![Vec::new(); self.count()];
+		for (node, &component) in self.components.iter().enumerate() {
+			members[component].push(node);
+		}
+		members
+	}
+
+	/// A graph with a node per component, and an edge where any edge goes from one component to
+	/// another. It has no cycles, and no edge twice.
+	pub fn condensation(&self) -> &Graph {
+		&self.condensation
+	}
+
+	/// The number of other components with an edge to this one.
+	pub fn in_degree(&self, component: usize) -> usize {
+		self.in_degrees[component]
+	}
+
+	/// The components no other component has an edge to.
+	pub fn sources(&self) -> Vec<usize> {
+		(0..self.count())
+			.filter(|&component| self.in_degrees[component] == 0)
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn dominoes() {
+		// 1 -> 2 -> 3 -> 1 knock each other over, 3 -> 4, and 5 on its own, numbered from 1
+		let lineups = [(1, 2), (2, 3), (3, 1), (3, 4), (3, 4)];
+		let graph = Graph::from_one_based(5, Direction::Directed, lineups);
+		let scc = graph.strongly_connected();
+		assert_eq!(scc.count(), 3);
+		assert_eq!(scc.members(), vec![vec![4], vec![0, 1, 2], vec![3]]);
+		assert_eq!(scc.components(), &[1, 1, 1, 2, 0]);
+		assert_eq!(scc.condensation().neighbors(1), &[2]);
+		assert_eq!(scc.condensation().edge_count(), 1);
+		assert_eq!((scc.in_degree(2), scc.component(3)), (1, 2));
+		assert_eq!(scc.sources(), vec![0, 1]);
+	}
+
+	#[test]
+	fn long_cycle_and_chain() {
+		let nodes = 100_000;
+		let around = (0..nodes).map(|n| (n, (n + 1) % nodes));
+		let cycle = Graph::from_edges(nodes, Direction::Directed, around);
+		assert_eq!(cycle.strongly_connected().count(), 1);
+		let chain = Graph::from_edges(nodes, Direction::Directed, (1..nodes).map(|n| (n, n - 1)));
+		let scc = chain.strongly_connected();
+		assert_eq!(scc.count(), nodes);
+		// topological order, so the last node comes first
+		assert_eq!((scc.component(nodes - 1), scc.component(0)), (0, nodes - 1));
+	}
+
+	#[test]
+	fn matches_reachability() {
+		const NODES: usize = 10;
+		property::check(
+			|rng| {
+				let node = |rng: &mut Rng| rng.range(0..=NODES - 1);
+				rng.vec(0..=20, |rng| (node(rng), node(rng)))
+			},
+			|edges| {
+				let graph = Graph::from_edges(NODES, Direction::Directed, edges.iter().copied());
+				let scc = graph.strongly_connected();
+				let reachable: Vec<Vec<bool>> =
+					(0..NODES).map(|node| graph.reachable_from(node)).collect();
+				let reaches = |a: usize, b: usize| reachable[a][b];
+				for a in 0..NODES {
+					for b in 0..NODES {
+						let same = scc.component(a) == scc.component(b);
+						ensure(same == (reaches(a, b) && reaches(b, a)), || {
+							format!("{} and {} in {:?}", a, b, scc.components())
+						})?;
+					}
+				}
+				for &(a, b) in edges.iter() {
+					let (from, to) = (scc.component(a), scc.component(b));
+					ensure(from <= to, || format!("{} -> {} is not in topological order", a, b))?;
+					ensure(from == to || scc.condensation().neighbors(from).contains(&to), || {
+						format!("{} -> {} is not in the condensation", a, b)
+					})?;
+				}
+				for component in 0..scc.count() {
+					let in_degree = (0..scc.count())
+						.filter(|&other| scc.condensation().neighbors(other).contains(&component))
+						.count();
+					ensure(scc.in_degree(component) == in_degree, || {
+						format!("in degree of {}", component)
+					})?;
+				}
+				Ok(())
+			}
+		);
+	}
+}
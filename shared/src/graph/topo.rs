@@ -0,0 +1,282 @@
+//! Orders of a directed graph where every edge goes forward (dependencies before what needs them),
+//! and dynamic programming along them.
+//!
+//! ```ignore
+//! let lineups = Graph::from_one_based(n, Direction::Directed, knocks_over);
+//! // how many dominoes the longest chain reaction knocks over one after another
+//! let chain = lineups.fold_dag(|_| 1, |&longest, &before, _| longest.max(before + 1))?;
+//! ```
+//! A graph with cycles has no such order, but its condensation
+//! (`graph.strongly_connected().condensation()`) always has.
+
+use std::{
+	cmp::Reverse,
+	collections::BinaryHeap,
+	fmt::{Display, Formatter}
+};
+
+use crate::graph::{Direction, Graph};
+
+/// The nodes of a cycle in edge order, the last one has an edge back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle(pub Vec<usize>);
+
+impl Display for Cycle {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		// back to the first node at the end
+		let around = self.0.iter().chain(self.0.first());
+		let nodes: Vec<String> = around.map(usize::to_string).collect();
+		write!(f, "cycle {}", nodes.join(" -> "))
+	}
+}
+
+impl Graph {
+	/// Kahn's algorithm: every node comes after all nodes with an edge to it.
+	pub fn topological_order(&self) -> Result<Vec<usize>, Cycle> {
+		let mut in_degrees = self.in_degrees();
+		let mut order: Vec<usize> =
+			(0..self.nodes()).filter(|&node| in_degrees[node] == 0).collect();
+		// the order is its own queue, everything after `done` still has to be taken off
+		let mut done = 0;
+		while let Some(&node) = order.get(done) {
+			done += 1;
+			for &next in self.neighbors(node) {
+				in_degrees[next] -= 1;
+				if in_degrees[next] == 0 {
+					order.push(next);
+				}
+			}
+		}
+		self.complete(order, &in_degrees)
+	}
+
+	/// The topological order that comes first when compared node by node
+	/// (always taking the smallest node that is ready), a log factor slower.
+	pub fn smallest_topological_order(&self) -> Result<Vec<usize>, Cycle> {
+		let mut in_degrees = self.in_degrees();
+		let mut ready: BinaryHeap<Reverse<usize>> =
+			(0..self.nodes()).filter(|&node| in_degrees[node] == 0).map(Reverse).collect();
+		let mut order = Vec::with_capacity(self.nodes());
+		while let Some(Reverse(node)) = ready.pop() {
+			order.push(node);
+			for &next in self.neighbors(node) {
+				in_degrees[next] -= 1;
+				if in_degrees[next] == 0 {
+					ready.push(Reverse(next));
+				}
+			}
+		}
+		self.complete(order, &in_degrees)
+	}
+
+	/// A cycle of the graph, if it has one (a self loop is a cycle of one node).
+	pub fn find_cycle(&self) -> Option<Cycle> {
+		self.topological_order().err()
+	}
+
+	/// Dynamic programming over a graph without cycles: every node starts at `start(node)`, then
+	/// in topological order every edge `node -> next` with index `edge` updates `next` to
+	/// `combine(&values[next], &values[node], edge)`. So a node has its final value before
+	/// anything after it is updated from it.
+	pub fn fold_dag<T>(
+		&self,
+		start: impl FnMut(usize) -> T,
+		mut combine: impl FnMut(&T, &T, usize) -> T
+	) -> Result<Vec<T>, Cycle> {
+		let order = self.topological_order()?;
+		let mut values: Vec<T> = (0..self.nodes()).map(start).collect();
+		for node in order {
+			for (next, edge) in self.edges(node) {
+				values[next] = combine(&values[next], &values[node], edge);
+			}
+		}
+		Ok(values)
+	}
+
+	// the number of edges into every node
+	fn in_degrees(&self) -> Vec<usize> {
+		assert!(
+			self.direction() == Direction::Directed,
+			"an undirected graph has no topological order"
+		);
+		let mut in_degrees = vec![0; self.nodes()];
+		for node in 0..self.nodes() {
+			for &next in self.neighbors(node) {
+				in_degrees[next] += 1;
+			}
+		}
+		in_degrees
+	}
+
+	// the order if Kahn's algorithm got to every node, otherwise a cycle among the nodes it
+	// could not get to (those still have an edge into them from another one of them)
+	fn complete(&self, order: Vec<usize>, in_degrees: &[usize]) -> Result<Vec<usize>, Cycle> {
+		if order.len() == self.nodes() {
+			return Ok(order);
+		}
+		let reversed = self.reversed();
+		let left = |node: usize| in_degrees[node] > 0;
+		// walk edges backwards until a node comes up again
+		let mut seen_at = vec![usize::MAX; self.nodes()];
+		let mut walk = Vec::new();
+		let mut node = (0..self.nodes()).find(|&node| left(node)).unwrap();
+		while seen_at[node] == usize::MAX {
+			seen_at[node] = walk.len();
+			walk.push(node);
+			node = *reversed.neighbors(node).iter().find(|&&before| left(before)).unwrap();
+		}
+		let mut cycle = walk.split_off(seen_at[node]);
+		cycle.reverse();
+		// starting from its smallest node, so the same cycle always comes out the same
+		let smallest = (0..cycle.len()).min_by_key(|&index| cycle[index]).unwrap();
+		cycle.rotate_left(smallest);
+		Err(Cycle(cycle))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{topo::Cycle, Direction, Graph},
+		property::{self, ensure},
+		random::Rng
+	};
+
+	#[test]
+	fn orders() {
+		// 5 -> 0 <- 4 -> 1, 2 -> 3 -> 1
+		let edges = [(5, 0), (4, 0), (4, 1), (2, 3), (3, 1)];
+		let graph = Graph::from_edges(6, Direction::Directed, edges);
+		assert_eq!(graph.topological_order(), Ok(vec![2, 4, 5, 3, 0, 1]));
+		assert_eq!(graph.smallest_topological_order(), Ok(vec![2, 3, 4, 1, 5, 0]));
+		assert_eq!(graph.find_cycle(), None);
+	}
+
+	#[test]
+	fn cycles() {
+		// 0 -> 1 -> 2 -> 3 -> 1, and 3 -> 4 after the cycle
+		let edges = [(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)];
+		let graph = Graph::from_edges(5, Direction::Directed, edges);
+		let cycle = graph.smallest_topological_order().unwrap_err();
+		assert_eq!(cycle, Cycle(vec![1, 2, 3]));
+		assert_eq!(cycle.to_string(), "cycle 1 -> 2 -> 3 -> 1");
+		let self_loop = Graph::from_edges(2, Direction::Directed, [(0, 1), (1, 1)]);
+		assert_eq!(self_loop.find_cycle(), Some(Cycle(vec![1])));
+		assert!(self_loop.fold_dag(|_| 0, |&a, _, _| a).is_err());
+	}
+
+	#[test]
+	fn longest_and_cheapest_paths() {
+		// 0 -> 1 -> 2 -> 3 and 0 -> 3, with a cost for every edge
+		let edges = [(0, 1), (1, 2), (2, 3), (0, 3)];
+		let costs = [1, 1, 1, 5];
+		let graph = Graph::from_edges(4, Direction::Directed, edges);
+		let longest = graph.fold_dag(|_| 0, |&longest, &before, _| longest.max(before + 1));
+		assert_eq!(longest, Ok(vec![0, 1, 2, 3]));
+		let cheapest = graph.fold_dag(
+			|node| if node == 0 { Some(0) } else { None },
+			|&cheapest: &Option<i32>, &before, edge| match (cheapest, before) {
+				(Some(cheapest), Some(before)) => Some(cheapest.min(before + costs[edge])),
+				(cheapest, before) => cheapest.or(before.map(|before| before + costs[edge]))
+			}
+		);
+		assert_eq!(cheapest, Ok(vec![Some(0), Some(1), Some(2), Some(3)]));
+		let paths = graph.fold_dag(|node| (node == 0) as u64, |&paths, &before, _| paths + before);
+		assert_eq!(paths, Ok(vec![1, 1, 1, 2]));
+	}
+
+	#[test]
+	fn long_chain() {
+		let nodes = 200_000;
+		let chain = Graph::from_edges(nodes, Direction::Directed, (1..nodes).map(|n| (n, n - 1)));
+		let order: Vec<usize> = (0..nodes).rev().collect();
+		assert_eq!(chain.smallest_topological_order(), Ok(order));
+		let around = (0..nodes).map(|n| (n, (n + 1) % nodes));
+		let cycle = Graph::from_edges(nodes, Direction::Directed, around);
+		assert_eq!(cycle.find_cycle().map(|cycle| cycle.0.len()), Some(nodes));
+	}
+
+	#[test]
+	fn condensation_has_an_order() {
+		let lineups = [(0, 1), (1, 0), (1, 2), (3, 2), (2, 4), (4, 3)];
+		let graph = Graph::from_edges(6, Direction::Directed, lineups);
+		assert!(graph.find_cycle().is_some());
+		let scc = graph.strongly_connected();
+		// the components are numbered in topological order already
+		let order: Vec<usize> = (0..scc.count()).collect();
+		assert_eq!(scc.condensation().smallest_topological_order(), Ok(order));
+		// pushing domino 2 knocks over 2, 3 and 4, but not 0 and 1 before it
+		let falls = scc.condensation().fold_dag(
+			|component| component == scc.component(2),
+			|&falls, &before, _| falls || before
+		);
+		let members = scc.members();
+		let fallen: Vec<usize> = falls
+			.unwrap()
+			.into_iter()
+			.enumerate()
+			.filter(|&(_, falls)| falls)
+			.flat_map(|(component, _)| members[component].iter().copied())
+			.collect();
+		assert_eq!(fallen, vec![2, 3, 4]);
+	}
+
+	#[test]
+	fn order_or_cycle() {
+		const NODES: usize = 10;
+		property::check(
+			|rng| {
+				let node = |rng: &mut Rng| rng.range(0..=NODES - 1);
+				let edges = rng.vec(0..=15, |rng| (node(rng), node(rng)));
+				// often only forward edges, so that there is an order
+				let forward = rng.chance(0.7);
+				let edges: Vec<(usize, usize)> = edges
+					.into_iter()
+					.map(|(a, b)| if forward { (a.min(b), a.max(b)) } else { (a, b) })
+					.filter(|&(a, b)| !forward || a != b)
+					.collect();
+				edges
+			},
+			|edges| {
+				let graph = Graph::from_edges(NODES, Direction::Directed, edges.iter().copied());
+				match (graph.topological_order(), graph.smallest_topological_order()) {
+					(Ok(order), Ok(smallest)) => {
+						for order in [&order, &smallest] {
+							let mut position = [usize::MAX; NODES];
+							for (index, &node) in order.iter().enumerate() {
+								position[node] = index;
+							}
+							ensure(position.iter().all(|&index| index < NODES), || {
+								format!("{:?} is not every node once", order)
+							})?;
+							for &(a, b) in edges.iter() {
+								ensure(position[a] < position[b], || {
+									format!("{} -> {} goes back in {:?}", a, b, order)
+								})?;
+							}
+						}
+						// no other order has a smaller node where they first differ
+						let smaller = order.iter().zip(smallest.iter()).find(|(a, b)| a != b);
+						ensure(smaller.is_none_or(|(a, b)| b < a), || {
+							format!("{:?} is smaller than {:?}", order, smallest)
+						})
+					}
+					(Err(Cycle(cycle)), Err(_)) => {
+						ensure(!cycle.is_empty(), || "empty cycle".to_string())?;
+						for (index, &node) in cycle.iter().enumerate() {
+							let next = cycle[(index + 1) % cycle.len()];
+							ensure(edges.contains(&(node, next)), || {
+								format!("{} -> {} of {:?} is not an edge", node, next, cycle)
+							})?;
+						}
+						let scc = graph.strongly_connected();
+						ensure(scc.count() < NODES || edges.iter().any(|(a, b)| a == b), || {
+							format!("{:?} without a component of two nodes or a self loop", cycle)
+						})
+					}
+					(a, b) => Err(format!("{:?} and {:?} disagree", a, b))
+				}
+			}
+		);
+	}
+}
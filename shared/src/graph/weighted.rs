@@ -0,0 +1,380 @@
+//! Shortest paths on graphs with weighted edges.
+//!
+//! ```ignore
+//! let graph = WeightedGraph::from_edges(n, Direction::Undirected, roads);
+//! let paths = graph.dijkstra(0);
+//! let route = paths.path_to(n - 1); // `None` if it cannot be reached
+//! ```
+//! The edges are kept in a [`Graph`], with the weight of every edge by its index next to it,
+//! so everything for unweighted graphs works on `graph.graph()` as well.
+//! Dijkstra needs weights of at least 0, and 0-1 BFS weights of exactly 0 or 1.
+//! Bellman-Ford takes any weights, and finds a negative cycle if there is one on the way.
+
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, VecDeque},
+	fmt::{Display, Formatter}
+};
+
+use crate::graph::{Direction, Graph};
+
+pub type Weight = i64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Edge {
+	pub to:     usize,
+	pub weight: Weight
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeightedGraph {
+	graph:   Graph,
+	weights: Vec<Weight> // by edge index
+}
+
+/// The distance to every node from a source, and the tree of shortest paths to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortestPaths {
+	distances: Vec<Option<Weight>>,
+	parents:   Vec<Option<usize>>
+}
+
+/// A cycle of negative weight reachable from the source, so some distances have no minimum.
+/// The nodes are in the order of the edges, the first one is not repeated at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegativeCycle(pub Vec<usize>);
+
+impl Display for NegativeCycle {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let nodes: Vec<String> = self.0.iter().map(usize::to_string).collect();
+		write!(f, "negative cycle {}", nodes.join(" -> "))
+	}
+}
+
+impl WeightedGraph {
+	/// A graph of `nodes` nodes with the edges `(from, to, weight)`, see `Graph::from_edges`.
+	pub fn from_edges(
+		nodes: usize,
+		direction: Direction,
+		edges: impl IntoIterator<Item = (usize, usize, Weight)>
+	) -> Self {
+		let (endpoints, weights): (Vec<_>, Vec<_>) =
+			edges.into_iter().map(|(from, to, weight)| ((from, to), weight)).unzip();
+		WeightedGraph {
+			graph: Graph::from_edges(nodes, direction, endpoints),
+			weights
+		}
+	}
+
+	/// The edges without their weights.
+	pub fn graph(&self) -> &Graph {
+		&self.graph
+	}
+
+	pub fn nodes(&self) -> usize {
+		self.graph.nodes()
+	}
+
+	/// The weight of an edge by its index in the input.
+	pub fn weight(&self, edge: usize) -> Weight {
+		self.weights[edge]
+	}
+
+	/// The edges from `node`, in the order of `Graph::edges`.
+	pub fn edges(&self, node: usize) -> impl Iterator<Item = Edge> + '_ {
+		self.graph.edges(node).map(|(to, id)| Edge {
+			to,
+			weight: self.weights[id]
+		})
+	}
+
+	fn unreached(&self, source: usize) -> ShortestPaths {
+		let mut paths = ShortestPaths {
+			distances: vec![None; self.nodes()],
+			parents:   vec![None; self.nodes()]
+		};
+		paths.distances[source] = Some(0);
+		paths
+	}
+
+	/// Panics on a negative weight.
+	pub fn dijkstra(&self, source: usize) -> ShortestPaths {
+		let mut paths = self.unreached(source);
+		let mut queue = BinaryHeap::from([Reverse((0, source))]);
+		while let Some(Reverse((distance, node))) = queue.pop() {
+			if paths.distances[node] != Some(distance) {
+				continue; // an outdated entry, the node was reached quicker since
+			}
+			for edge in self.edges(node) {
+				assert!(edge.weight >= 0, "Dijkstra cannot take the weight {}", edge.weight);
+				let next = distance + edge.weight;
+				if paths.distances[edge.to].is_none_or(|old| next < old) {
+					paths.distances[edge.to] = Some(next);
+					paths.parents[edge.to] = Some(node);
+					queue.push(Reverse((next, edge.to)));
+				}
+			}
+		}
+		paths
+	}
+
+	/// Like BFS, but edges of weight 0 go to the front of the queue. Panics on other weights.
+	pub fn zero_one_bfs(&self, source: usize) -> ShortestPaths {
+		let mut paths = self.unreached(source);
+		let mut done = vec![false; self.nodes()];
+		let mut queue = VecDeque::from([source]);
+		while let Some(node) = queue.pop_front() {
+			if std::mem::replace(&mut done[node], true) {
+				continue;
+			}
+			let distance = paths.distances[node].unwrap();
+			for edge in self.edges(node) {
+				let next = distance + edge.weight;
+				if paths.distances[edge.to].is_none_or(|old| next < old) {
+					paths.distances[edge.to] = Some(next);
+					paths.parents[edge.to] = Some(node);
+					match edge.weight {
+						0 => queue.push_front(edge.to),
+						1 => queue.push_back(edge.to),
+						weight => panic!("0-1 BFS cannot take the weight {}", weight)
+					}
+				}
+			}
+		}
+		paths
+	}
+
+	/// Relaxes every edge until nothing changes. If something still changes after as many rounds
+	/// as there are nodes, there is a negative cycle.
+	pub fn bellman_ford(&self, source: usize) -> Result<ShortestPaths, NegativeCycle> {
+		let mut paths = self.unreached(source);
+		let mut relaxed = None;
+		for _ in 0..self.nodes() {
+			relaxed = None;
+			for node in 0..self.nodes() {
+				let Some(distance) = paths.distances[node] else {
+					continue;
+				};
+				for edge in self.edges(node) {
+					let next = distance + edge.weight;
+					if paths.distances[edge.to].is_none_or(|old| next < old) {
+						paths.distances[edge.to] = Some(next);
+						paths.parents[edge.to] = Some(node);
+						relaxed = Some(edge.to);
+					}
+				}
+			}
+			if relaxed.is_none() {
+				return Ok(paths);
+			}
+		}
+		// walking back from the last change long enough ends up on the cycle
+		let mut node = relaxed.unwrap();
+		for _ in 0..self.nodes() {
+			node = paths.parents[node].unwrap();
+		}
+		Err(NegativeCycle(cycle_through(&paths.parents, node)))
+	}
+}
+
+// the nodes of the parent cycle `node` is on, in edge order
+fn cycle_through(parents: &[Option<usize>], node: usize) -> Vec<usize> {
+	let mut cycle = vec![node];
+	let mut current = parents[node].unwrap();
+	while current != node {
+		cycle.push(current);
+		current = parents[current].unwrap();
+	}
+	cycle.reverse();
+	cycle
+}
+
+impl ShortestPaths {
+	/// `None` if the node cannot be reached from the source.
+	pub fn distance(&self, node: usize) -> Option<Weight> {
+		self.distances[node]
+	}
+
+	pub fn distances(&self) -> &[Option<Weight>] {
+		&self.distances
+	}
+
+	/// The node before this one on its shortest path, `None` for the source and unreached nodes.
+	pub fn parent(&self, node: usize) -> Option<usize> {
+		self.parents[node]
+	}
+
+	/// A shortest path from the source to the node, both included.
+	pub fn path_to(&self, node: usize) -> Option<Vec<usize>> {
+		self.distances[node]?;
+		let mut path = vec![node];
+		while let Some(parent) = self.parents[*path.last().unwrap()] {
+			path.push(parent);
+		}
+		path.reverse();
+		Some(path)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{
+			weighted::{Edge, NegativeCycle, ShortestPaths, Weight, WeightedGraph},
+			Direction
+		},
+		property::{self, ensure, Shrink}
+	};
+
+	#[derive(Debug, Clone)]
+	struct Case {
+		nodes: usize,
+		edges: Vec<(usize, usize, Weight)>
+	}
+	impl Shrink for Case {
+		// drop one edge at a time
+		fn shrink(&self) -> Vec<Self> {
+			(0..self.edges.len())
+				.map(|index| {
+					let mut case = self.clone();
+					case.edges.remove(index);
+					case
+				})
+				.collect()
+		}
+	}
+	impl Case {
+		fn generate(rng: &mut crate::random::Rng, weights: &[Weight]) -> Self {
+			let nodes = rng.range(1..=8);
+			let edges = (0..rng.range(0..=20))
+				.map(|_| (rng.range(0..=nodes - 1), rng.range(0..=nodes - 1), *rng.choose(weights)))
+				.collect();
+			Case { nodes, edges }
+		}
+
+		fn graph(&self) -> WeightedGraph {
+			WeightedGraph::from_edges(self.nodes, Direction::Directed, self.edges.iter().copied())
+		}
+
+		// Floyd-Warshall, `None` where there is no path
+		fn brute_force(&self) -> Vec<Vec<Option<Weight>>> {
+			let mut distances = vec![vec![None; self.nodes]; self.nodes];
+			for (node, row) in distances.iter_mut().enumerate() {
+				row[node] = Some(0);
+			}
+			for &(from, to, weight) in self.edges.iter() {
+				let old: &mut Option<Weight> = &mut distances[from][to];
+				*old = Some(old.map_or(weight, |old| old.min(weight)));
+			}
+			for via in 0..self.nodes {
+				for from in 0..self.nodes {
+					for to in 0..self.nodes {
+						if let (Some(a), Some(b)) = (distances[from][via], distances[via][to]) {
+							let old = &mut distances[from][to];
+							*old = Some(old.map_or(a + b, |old| old.min(a + b)));
+						}
+					}
+				}
+			}
+			distances
+		}
+
+		fn weight(&self, from: usize, to: usize) -> Option<Weight> {
+			self.edges
+				.iter()
+				.filter(|edge| (edge.0, edge.1) == (from, to))
+				.map(|edge| edge.2)
+				.min()
+		}
+
+		// the distances match the brute force, and every parent edge is on a shortest path
+		fn check(&self, paths: &ShortestPaths) -> Result<(), String> {
+			let expected = &self.brute_force()[0];
+			ensure(paths.distances() == expected, || {
+				format!("expected {:?}, got {:?}", expected, paths.distances())
+			})?;
+			for node in 1..self.nodes {
+				let Some(path) = paths.path_to(node) else {
+					continue;
+				};
+				let length: Option<Weight> =
+					path.windows(2).map(|step| self.weight(step[0], step[1])).sum();
+				ensure(path[0] == 0 && length == paths.distance(node), || {
+					format!("path {:?} to {} is not {:?} long", path, node, paths.distance(node))
+				})?;
+			}
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn dijkstra_matches_brute_force() {
+		property::check(|rng| Case::generate(rng, &[0, 1, 2, 5, 10]), |case| {
+			case.check(&case.graph().dijkstra(0))
+		});
+	}
+
+	#[test]
+	fn zero_one_bfs_matches_brute_force() {
+		property::check(|rng| Case::generate(rng, &[0, 1]), |case| {
+			case.check(&case.graph().zero_one_bfs(0))
+		});
+	}
+
+	#[test]
+	fn bellman_ford_matches_brute_force() {
+		property::check(|rng| Case::generate(rng, &[-3, -1, 0, 2, 4, 7]), |case| {
+			let brute_force = case.brute_force();
+			// a node on a negative cycle gets closer to itself
+			let negative_cycle = (0..case.nodes)
+				.any(|node| brute_force[0][node].is_some() && brute_force[node][node] < Some(0));
+			match case.graph().bellman_ford(0) {
+				Ok(paths) => {
+					ensure(!negative_cycle, || "missed a negative cycle".to_string())?;
+					case.check(&paths)
+				},
+				Err(NegativeCycle(cycle)) => {
+					ensure(negative_cycle, || format!("{:?} cannot be a negative cycle", cycle))?;
+					let weight: Option<Weight> = (0..cycle.len())
+						.map(|index| case.weight(cycle[index], cycle[(index + 1) % cycle.len()]))
+						.sum();
+					ensure(weight.is_some_and(|weight| weight < 0), || {
+						format!("{:?} is not a negative cycle", cycle)
+					})
+				}
+			}
+		});
+	}
+
+	#[test]
+	fn undirected_roads() {
+		let graph = WeightedGraph::from_edges(4, Direction::Undirected, [
+			(0, 1, 5),
+			(1, 2, 1),
+			(2, 0, 1),
+			(3, 3, 4)
+		]);
+		let paths = graph.dijkstra(1);
+		assert_eq!(paths.distances(), &[Some(2), Some(0), Some(1), None]);
+		assert_eq!(paths.path_to(0), Some(vec![1, 2, 0]));
+		assert_eq!(paths.parent(1), None);
+		assert_eq!(paths.path_to(3), None);
+		assert_eq!(graph.edges(3).collect::<Vec<_>>(), vec![Edge { to: 3, weight: 4 }]);
+		assert_eq!(graph.graph().neighbors(0), &[1, 2]);
+		assert_eq!(graph.weight(2), 1);
+	}
+
+	#[test]
+	fn negative_self_loop() {
+		let graph = WeightedGraph::from_edges(2, Direction::Directed, [(0, 1, 1), (1, 1, -1)]);
+		let cycle = graph.bellman_ford(0).unwrap_err();
+		assert_eq!(cycle, NegativeCycle(vec![1]));
+		assert_eq!(cycle.to_string(), "negative cycle 1");
+	}
+
+	#[test]
+	#[should_panic(expected = "Dijkstra cannot take the weight -1")]
+	fn dijkstra_negative_weight() {
+		WeightedGraph::from_edges(2, Direction::Directed, [(0, 1, -1)]).dijkstra(0);
+	}
+}
@@ -0,0 +1,375 @@
+//! A rectangular board of cells, like the chess board of knightjump.
+//!
+//! Points are signed, so a move off the board is just a point that `get` says nothing about:
+//! ```ignore
+//! let mut knight = None;
+//! let board = Grid::parse(input, |point, c| match c {
+//!     'K' => {
+//!         knight = Some(point);
+//!         Ok(Square::Empty)
+//!     },
+//!     '.' => Ok(Square::Empty),
+//!     '#' => Ok(Square::Blocked),
+//!     c => Err(format!("unknown square `{}`", c))
+//! })?;
+//! for next in board.neighbors(knight.unwrap(), &KNIGHT) { ... }
+//! ```
+//! Solutions still have to carry their own board for submission, this is for tests and tooling.
+
+use std::{
+	fmt::{Display, Formatter},
+	ops::{Add, Index, IndexMut, Sub}
+};
+
+/// `x` is the column and `y` the row, counting from the top left.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+	pub x: isize,
+	pub y: isize
+}
+
+impl Point {
+	pub const fn new(x: isize, y: isize) -> Self {
+		Point { x, y }
+	}
+}
+
+impl Add for Point {
+	type Output = Point;
+
+	fn add(self, rhs: Point) -> Self::Output {
+		Point::new(self.x + rhs.x, self.y + rhs.y)
+	}
+}
+
+impl Sub for Point {
+	type Output = Point;
+
+	fn sub(self, rhs: Point) -> Self::Output {
+		Point::new(self.x - rhs.x, self.y - rhs.y)
+	}
+}
+
+impl Display for Point {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "({}, {})", self.x, self.y)
+	}
+}
+
+/// Up, right, down and left.
+pub const ORTHOGONAL: [Point; 4] =
+	[Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)];
+
+/// The orthogonal neighbors and the diagonal ones, clockwise from the top left.
+pub const ADJACENT: [Point; 8] = [
+	Point::new(-1, -1),
+	Point::new(0, -1),
+	Point::new(1, -1),
+	Point::new(1, 0),
+	Point::new(1, 1),
+	Point::new(0, 1),
+	Point::new(-1, 1),
+	Point::new(-1, 0)
+];
+
+pub const KNIGHT: [Point; 8] = [
+	Point::new(2, 1),
+	Point::new(2, -1),
+	Point::new(-2, 1),
+	Point::new(-2, -1),
+	Point::new(1, 2),
+	Point::new(1, -2),
+	Point::new(-1, 2),
+	Point::new(-1, -2)
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+	width:  usize,
+	height: usize,
+	cells:  Vec<T> // width * height, row by row
+}
+
+impl<T> Grid<T> {
+	pub fn new(width: usize, height: usize, cell: T) -> Self
+	where
+		T: Clone
+	{
+		Grid {
+			width,
+			height,
+			cells: vec![cell; width * height]
+		}
+	}
+
+	pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Point) -> T) -> Self {
+		let cells = (0..height)
+			.flat_map(|y| (0..width).map(move |x| Point::new(x as isize, y as isize)))
+			.map(&mut cell)
+			.collect();
+		Grid {
+			width,
+			height,
+			cells
+		}
+	}
+
+	/// Rows of equal length, top to bottom.
+	pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, String> {
+		let width = rows.first().map_or(0, Vec::len);
+		let height = rows.len();
+		let mut cells = Vec::with_capacity(width * height);
+		for (y, row) in rows.into_iter().enumerate() {
+			if row.len() != width {
+				return Err(format!(
+					"expected {} cells in row {}, found {}",
+					width,
+					y + 1,
+					row.len()
+				));
+			}
+			cells.extend(row);
+		}
+		Ok(Grid {
+			width,
+			height,
+			cells
+		})
+	}
+
+	/// A map with one row per line, every character turned into a cell by `cell`.
+	/// The closure also gets the point of the character, to remember markers like a `K`.
+	pub fn parse(
+		map: &str,
+		mut cell: impl FnMut(Point, char) -> Result<T, String>
+	) -> Result<Self, String> {
+		let rows = map
+			.lines()
+			.enumerate()
+			.map(|(y, line)| {
+				line.chars()
+					.enumerate()
+					.map(|(x, c)| {
+						cell(Point::new(x as isize, y as isize), c)
+							.map_err(|e| format!("at column {} of row {}: {}", x + 1, y + 1, e))
+					})
+					.collect::<Result<Vec<T>, String>>()
+			})
+			.collect::<Result<Vec<_>, String>>()?;
+		Grid::from_rows(rows)
+	}
+
+	pub fn width(&self) -> usize {
+		self.width
+	}
+
+	pub fn height(&self) -> usize {
+		self.height
+	}
+
+	pub fn contains(&self, point: Point) -> bool {
+		(0..self.width as isize).contains(&point.x) && (0..self.height as isize).contains(&point.y)
+	}
+
+	fn index(&self, point: Point) -> Option<usize> {
+		self.contains(point)
+			.then(|| point.y as usize * self.width + point.x as usize)
+	}
+
+	/// `None` outside of the grid.
+	pub fn get(&self, point: Point) -> Option<&T> {
+		self.index(point).map(|index| &self.cells[index])
+	}
+
+	pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+		self.index(point).map(|index| &mut self.cells[index])
+	}
+
+	/// Every point of the grid, row by row.
+	pub fn points(&self) -> impl Iterator<Item = Point> {
+		let width = self.width;
+		(0..self.cells.len())
+			.map(move |index| Point::new((index % width) as isize, (index / width) as isize))
+	}
+
+	/// Every cell with its point, row by row.
+	pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+		self.points().zip(self.cells.iter())
+	}
+
+	pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+		// `chunks` does not take 0, and an empty row has nothing to chunk anyway
+		self.cells.chunks(self.width.max(1))
+	}
+
+	pub fn row(&self, y: usize) -> Option<&[T]> {
+		(y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+	}
+
+	pub fn column(&self, x: usize) -> Option<impl Iterator<Item = &T>> {
+		(x < self.width).then(|| self.cells.iter().skip(x).step_by(self.width))
+	}
+
+	pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+		(0..self.width).map(move |x| self.cells.iter().skip(x).step_by(self.width))
+	}
+
+	/// The points `offsets` away from `point` that are still inside the grid,
+	/// like `ORTHOGONAL`, `ADJACENT` or `KNIGHT`.
+	pub fn neighbors<'a>(
+		&'a self,
+		point: Point,
+		offsets: &'a [Point]
+	) -> impl Iterator<Item = Point> + 'a {
+		offsets
+			.iter()
+			.map(move |&offset| point + offset)
+			.filter(|&neighbor| self.contains(neighbor))
+	}
+
+	pub fn map<U>(&self, mut cell: impl FnMut(Point, &T) -> U) -> Grid<U> {
+		Grid {
+			width:  self.width,
+			height: self.height,
+			cells:  self.iter().map(|(point, value)| cell(point, value)).collect()
+		}
+	}
+
+	/// Back to a map like `parse` reads, one line per row.
+	pub fn to_map(&self, mut symbol: impl FnMut(&T) -> char) -> String {
+		let mut map = String::with_capacity((self.width + 1) * self.height);
+		for row in self.rows().take(self.height) {
+			map.extend(row.iter().map(&mut symbol));
+			map.push('\n');
+		}
+		map
+	}
+}
+
+impl<T> Index<Point> for Grid<T> {
+	type Output = T;
+
+	fn index(&self, point: Point) -> &Self::Output {
+		let (width, height) = (self.width, self.height);
+		self.get(point)
+			.unwrap_or_else(|| panic!("{} is outside of a {}x{} grid", point, width, height))
+	}
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+	fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+		let (width, height) = (self.width, self.height);
+		self.get_mut(point)
+			.unwrap_or_else(|| panic!("{} is outside of a {}x{} grid", point, width, height))
+	}
+}
+
+/// Cells that are characters, or can be turned back into one.
+impl<T: Copy + Into<char>> Display for Grid<T> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.to_map(|&cell| cell.into()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::grid::{Grid, Point, ADJACENT, KNIGHT, ORTHOGONAL};
+
+	#[derive(Debug, Clone, Copy, PartialEq)]
+	enum Square {
+		Empty,
+		Blocked
+	}
+	impl From<Square> for char {
+		fn from(square: Square) -> Self {
+			match square {
+				Square::Empty => '.',
+				Square::Blocked => '#'
+			}
+		}
+	}
+
+	fn board(map: &str) -> Result<(Grid<Square>, Option<Point>), String> {
+		let mut knight = None;
+		let grid = Grid::parse(map, |point, c| match c {
+			'K' => {
+				knight = Some(point);
+				Ok(Square::Empty)
+			},
+			'.' => Ok(Square::Empty),
+			'#' => Ok(Square::Blocked),
+			c => Err(format!("unknown square `{}`", c))
+		})?;
+		Ok((grid, knight))
+	}
+
+	#[test]
+	fn parse_with_markers() {
+		let (grid, knight) = board("..K.\n....\n###.\n").unwrap();
+		assert_eq!((grid.width(), grid.height()), (4, 3));
+		assert_eq!(knight, Some(Point::new(2, 0)));
+		assert_eq!(grid[Point::new(0, 2)], Square::Blocked);
+		assert_eq!(grid.to_string(), "....\n....\n###.\n");
+		let holes = grid.to_map(|&square| if square == Square::Empty { ' ' } else { '#' });
+		assert_eq!(holes, "    \n    \n### \n");
+	}
+
+	#[test]
+	fn parse_errors() {
+		assert_eq!(board("..\n.x\n").unwrap_err(), "at column 2 of row 2: unknown square `x`");
+		assert_eq!(board("..\n...\n").unwrap_err(), "expected 2 cells in row 2, found 3");
+		let (empty, knight) = board("").unwrap();
+		assert_eq!((empty.width(), empty.height(), knight), (0, 0, None));
+		assert_eq!(empty.rows().count(), 0);
+	}
+
+	#[test]
+	fn bounds() {
+		let mut grid = Grid::new(3, 2, 0);
+		assert!(grid.get(Point::new(-1, 0)).is_none());
+		assert!(grid.get(Point::new(3, 0)).is_none());
+		assert!(grid.get(Point::new(0, 2)).is_none());
+		*grid.get_mut(Point::new(2, 1)).unwrap() = 5;
+		grid[Point::new(0, 1)] = 1;
+		assert_eq!(grid.row(1), Some(&[1, 0, 5][..]));
+		assert_eq!(grid.row(2), None);
+	}
+
+	#[test]
+	#[should_panic(expected = "(3, 0) is outside of a 3x2 grid")]
+	fn index_outside() {
+		let _ = Grid::new(3, 2, 0)[Point::new(3, 0)];
+	}
+
+	#[test]
+	fn rows_and_columns() {
+		let grid = Grid::from_fn(3, 2, |point| point.y * 10 + point.x);
+		let rows: Vec<&[isize]> = grid.rows().collect();
+		assert_eq!(rows, vec![&[0, 1, 2][..], &[10, 11, 12][..]]);
+		let columns: Vec<Vec<isize>> =
+			grid.columns().map(|column| column.copied().collect()).collect();
+		assert_eq!(columns, vec![vec![0, 10], vec![1, 11], vec![2, 12]]);
+		assert_eq!(grid.column(1).unwrap().copied().collect::<Vec<_>>(), vec![1, 11]);
+		assert!(grid.column(3).is_none());
+		assert_eq!(grid.iter().nth(4), Some((Point::new(1, 1), &11)));
+		let digits = grid.map(|_, &cell| cell % 10);
+		assert_eq!(digits.to_map(|&cell| (b'0' + cell as u8) as char), "012\n012\n");
+	}
+
+	#[test]
+	fn neighbors() {
+		let grid = Grid::new(3, 3, ());
+		let corner = Point::new(0, 0);
+		let center = Point::new(1, 1);
+		assert_eq!(grid.neighbors(corner, &ORTHOGONAL).collect::<Vec<_>>(), vec![
+			Point::new(1, 0),
+			Point::new(0, 1)
+		]);
+		assert_eq!(grid.neighbors(corner, &ADJACENT).count(), 3);
+		assert_eq!(grid.neighbors(center, &ADJACENT).count(), 8);
+		assert_eq!(grid.neighbors(center, &KNIGHT).count(), 0);
+		assert_eq!(grid.neighbors(corner, &KNIGHT).count(), 2);
+		let custom = [Point::new(2, 2), Point::new(3, 3)];
+		assert_eq!(grid.neighbors(corner, &custom).collect::<Vec<_>>(), vec![Point::new(2, 2)]);
+	}
+}
@@ -0,0 +1,328 @@
+use std::{convert::TryInto, fmt::Display, str::FromStr};
+
+use crate::{
+	normalize::{normalize, Whitespace},
+	scanner::{ParseError, Scanner}
+};
+
+/// Something that can be read from (a part of) a problem input.
+/// Usually derived with `#[derive(KattisInput)]`, see the crate docs for the layout attributes.
+pub trait KattisInput: Sized {
+	fn read(scanner: &mut Scanner<'_>) -> Result<Self, ParseError>;
+}
+
+macro_rules! token_input {
+	($($t:ty),*) => {
+		$(
+			impl KattisInput for $t {
+				fn read(scanner: &mut Scanner<'_>) -> Result<Self, ParseError> {
+					scanner.token()
+				}
+			}
+		)*
+	};
+}
+token_input!(
+	u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char, bool, String
+);
+
+/// Reads the whole input as `T`, and checks that nothing is left afterwards.
+/// A byte order mark and CRLF line endings are fine, see `normalize`.
+pub fn parse<T: KattisInput>(input: &str) -> Result<T, ParseError> {
+	let input = normalize(input, Whitespace::Significant);
+	let mut scanner = Scanner::new(&input);
+	let value = T::read(&mut scanner)?;
+	scanner.finish()?;
+	Ok(value)
+}
+
+// the functions below are what `#[derive(KattisInput)]` expands to
+
+pub fn count<N: TryInto<usize>>(scanner: &Scanner<'_>, count: N) -> Result<usize, ParseError> {
+	count
+		.try_into()
+		.map_err(|_| scanner.error("count does not fit in a usize"))
+}
+
+/// The rest of the current line as a single value.
+pub fn read_line<T>(scanner: &mut Scanner<'_>) -> Result<T, ParseError>
+where
+	T: FromStr,
+	T::Err: Display
+{
+	let start = scanner.position();
+	let line = scanner.line()?;
+	line.parse().map_err(|e| {
+		scanner.error_at(
+			start,
+			format!(
+				"could not read line `{}` as {}: {}",
+				line,
+				std::any::type_name::<T>(),
+				e
+			)
+		)
+	})
+}
+
+/// Exactly `count` elements, either on the current line or one per line.
+pub fn read_counted<'a, T>(
+	scanner: &mut Scanner<'a>,
+	count: usize,
+	per_line: bool,
+	mut element: impl FnMut(&mut Scanner<'a>) -> Result<T, ParseError>
+) -> Result<Vec<T>, ParseError> {
+	// every element takes at least one byte, so a huge count in a bad input cannot run out of memory
+	let mut elements = Vec::with_capacity(count.min(scanner.input().len()));
+	for _ in 0..count {
+		if per_line {
+			scanner.end_line()?;
+		}
+		elements.push(element(scanner)?);
+	}
+	Ok(elements)
+}
+
+/// Elements until the end of the current line.
+pub fn read_until_line_end<'a, T>(
+	scanner: &mut Scanner<'a>,
+	mut element: impl FnMut(&mut Scanner<'a>) -> Result<T, ParseError>
+) -> Result<Vec<T>, ParseError> {
+	let mut elements = Vec::new();
+	while !scanner.at_line_end() {
+		elements.push(element(scanner)?);
+	}
+	Ok(elements)
+}
+
+/// Elements until the end of the input, either whitespace separated or one per line.
+pub fn read_until_end<'a, T>(
+	scanner: &mut Scanner<'a>,
+	per_line: bool,
+	mut element: impl FnMut(&mut Scanner<'a>) -> Result<T, ParseError>
+) -> Result<Vec<T>, ParseError> {
+	let mut elements = Vec::new();
+	while !scanner.at_end() {
+		if per_line {
+			scanner.end_line()?;
+		} else {
+			scanner.skip_whitespace();
+		}
+		elements.push(element(scanner)?);
+	}
+	Ok(elements)
+}
+
+/// `rows` lines of characters, each starting on a new line.
+/// If `columns` is given every row has to be exactly that long.
+pub fn read_grid<T>(
+	scanner: &mut Scanner<'_>,
+	rows: usize,
+	columns: Option<usize>
+) -> Result<Vec<Vec<T>>, ParseError>
+where
+	T: TryFrom<char>,
+	T::Error: Display
+{
+	let mut grid = Vec::with_capacity(rows.min(scanner.input().len()));
+	for _ in 0..rows {
+		scanner.end_line()?;
+		let start = scanner.position();
+		let line = scanner.line()?;
+		if let Some(columns) = columns {
+			let length = line.chars().count();
+			if length != columns {
+				return Err(scanner.error_at(
+					start,
+					format!("expected a row of {} cells, found {}", columns, length)
+				));
+			}
+		}
+		let row = line
+			.chars()
+			.enumerate()
+			.map(|(column, c)| {
+				T::try_from(c).map_err(|e| {
+					let mut position = start;
+					position.column += column;
+					scanner.error_at(position, format!("invalid cell `{}`: {}", c, e))
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		grid.push(row);
+	}
+	Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		input::parse,
+		normalize::{messy, Whitespace},
+		scanner::{ParseError, Position},
+		KattisInput
+	};
+
+	// refrigerator: everything on one line, read through a nested struct
+	#[derive(Debug, PartialEq, KattisInput)]
+	struct Car {
+		cost_per_trip: usize,
+		capacity:      usize
+	}
+	#[derive(Debug, PartialEq, KattisInput)]
+	struct Refrigerator {
+		car_a:         Car,
+		car_b:         Car,
+		refrigerators: usize
+	}
+
+	// knightjump: size, then a square board
+	#[derive(Debug, KattisInput)]
+	struct KnightJump {
+		n:     usize,
+		#[kattis(grid(rows = n, cols = n))]
+		board: Vec<Vec<char>>
+	}
+
+	// theseus: two counts, then one word per line
+	#[derive(Debug, KattisInput)]
+	struct Theseus {
+		p:   usize,
+		n:   usize,
+		#[kattis(count = n, per_line)]
+		w_i: Vec<String>
+	}
+
+	// jamboree: two counts, then a line of items
+	#[derive(Debug, KattisInput)]
+	struct Jamboree {
+		n:     usize,
+		m:     usize,
+		#[kattis(newline, count = n)]
+		items: Vec<usize>
+	}
+
+	#[derive(Debug, KattisInput)]
+	struct Remaining {
+		#[kattis(line)]
+		title: String,
+		#[kattis(rest, per_line, line)]
+		lines: Vec<String>
+	}
+
+	#[derive(Debug, KattisInput)]
+	struct UntilLineEnd {
+		values: Vec<i32>,
+		#[kattis(newline)]
+		last:   i32
+	}
+
+	#[test]
+	fn refrigerator_sample() {
+		let problem: Refrigerator = include_str!("../../kattis/refrigerator/src/1.in")
+			.parse()
+			.unwrap();
+		assert_eq!(problem, Refrigerator {
+			car_a:         Car {
+				cost_per_trip: 960,
+				capacity:      13
+			},
+			car_b:         Car {
+				cost_per_trip: 995,
+				capacity:      14
+			},
+			refrigerators: 150
+		});
+	}
+
+	#[test]
+	fn knightjump_samples() {
+		let problem: KnightJump = include_str!("../../kattis/knightjump/src/2.in")
+			.parse()
+			.unwrap();
+		assert_eq!(problem.n, 3);
+		assert_eq!(problem.board, vec![
+			vec!['.', '.', 'K'],
+			vec!['.', '.', '.'],
+			vec!['#', '#', '#']
+		]);
+		let problem: KnightJump = include_str!("../../kattis/knightjump/src/1.in")
+			.parse()
+			.unwrap();
+		assert_eq!(problem.board[3][3], 'K');
+	}
+
+	#[test]
+	fn knightjump_short_row() {
+		let error = "3\n..K\n..\n###\n".parse::<KnightJump>().unwrap_err();
+		assert_eq!(error.position, Position { line: 3, column: 1 });
+	}
+
+	#[test]
+	fn theseus_sample() {
+		let problem: Theseus = include_str!("../../kattis/challenges/theseus/src/in_1.txt")
+			.parse()
+			.unwrap();
+		assert_eq!((problem.p, problem.n), (3, 5));
+		assert_eq!(problem.w_i, vec![
+			"left_oar",
+			"right_oar",
+			"left_oar",
+			"hull",
+			"right_oar"
+		]);
+	}
+
+	#[test]
+	fn messy_theseus_sample() {
+		let sample = include_str!("../../kattis/challenges/theseus/src/in_1.txt");
+		let problem: Theseus = messy(sample, Whitespace::Insignificant).parse().unwrap();
+		assert_eq!((problem.p, problem.n), (3, 5));
+		assert_eq!(problem.w_i[0], "left_oar");
+	}
+
+	#[test]
+	fn theseus_missing_word() {
+		let error = "3 3\nhull\nhull\n".parse::<Theseus>().unwrap_err();
+		assert_eq!(error.position, Position { line: 4, column: 1 });
+	}
+
+	#[test]
+	fn jamboree_samples() {
+		let problem: Jamboree = include_str!("../../dm-2023/jamboree/src/1.in")
+			.parse()
+			.unwrap();
+		assert_eq!((problem.n, problem.m), (3, 4));
+		assert_eq!(problem.items, vec![10, 10, 10]);
+		let problem: Jamboree = include_str!("../../dm-2023/jamboree/src/2.in")
+			.parse()
+			.unwrap();
+		assert_eq!(problem.items.len(), problem.n);
+	}
+
+	#[test]
+	fn jamboree_items_on_wrong_line() {
+		let error = "3 4 10\n10 10\n".parse::<Jamboree>().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 5 });
+	}
+
+	#[test]
+	fn rest_of_input() {
+		let problem: Remaining = "a title\nfirst line\nsecond  line\n".parse().unwrap();
+		assert_eq!(problem.title, "a title");
+		assert_eq!(problem.lines, vec!["first line", "second  line"]);
+	}
+
+	#[test]
+	fn values_until_line_end() {
+		let problem: UntilLineEnd = "1 -2 3\n4".parse().unwrap();
+		assert_eq!(problem.values, vec![1, -2, 3]);
+		assert_eq!(problem.last, 4);
+	}
+
+	#[test]
+	fn trailing_input_is_an_error() {
+		let error: ParseError = parse::<usize>("1 2").unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 3 });
+	}
+}
@@ -0,0 +1,104 @@
+//! Line based back-and-forth for interactive problems.
+//! Used on both sides: by solutions talking to the judge, and by the interactors
+//! (`src/bin/interactor.rs` in the problem crate) the local judge runs them against.
+//! Interactors get their test case files from [`crate::verdict::test_case_files`].
+
+use std::{
+	fmt::Display,
+	io::{self, stdin, stdout, BufRead, Error, ErrorKind, StdinLock, StdoutLock, Write},
+	str::FromStr
+};
+
+pub struct Interaction<R, W> {
+	reader:  R,
+	writer:  W,
+	queries: usize
+}
+
+impl Interaction<StdinLock<'static>, StdoutLock<'static>> {
+	pub fn stdio() -> Self {
+		Interaction::new(stdin().lock(), stdout().lock())
+	}
+}
+
+impl<R: BufRead, W: Write> Interaction<R, W> {
+	pub fn new(reader: R, writer: W) -> Self {
+		Interaction {
+			reader,
+			writer,
+			queries: 0
+		}
+	}
+
+	/// Number of queries asked so far.
+	pub fn queries(&self) -> usize {
+		self.queries
+	}
+
+	/// Next line without surrounding whitespace, an error if the other side hung up.
+	pub fn read_line(&mut self) -> io::Result<String> {
+		let mut line = String::new();
+		if self.reader.read_line(&mut line)? == 0 {
+			return Err(Error::new(
+				ErrorKind::UnexpectedEof,
+				"the other side closed the connection"
+			));
+		}
+		Ok(line.trim().to_string())
+	}
+
+	pub fn read<T>(&mut self) -> io::Result<T>
+	where
+		T: FromStr,
+		T::Err: Display
+	{
+		let line = self.read_line()?;
+		line.parse().map_err(|e| {
+			Error::new(
+				ErrorKind::InvalidData,
+				format!("could not read `{}`: {}", line, e)
+			)
+		})
+	}
+
+	/// Writes a line and flushes it, so the other side actually gets to see it.
+	pub fn send(&mut self, message: impl Display) -> io::Result<()> {
+		writeln!(self.writer, "{}", message)?;
+		self.writer.flush()
+	}
+
+	/// Sends a line and waits for the reply.
+	pub fn query(&mut self, message: impl Display) -> io::Result<String> {
+		self.queries += 1;
+		self.send(message)?;
+		self.read_line()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{Cursor, ErrorKind};
+
+	use crate::interactive::Interaction;
+
+	#[test]
+	fn query_sends_and_reads() {
+		let mut written = Vec::new();
+		let mut interaction = Interaction::new(Cursor::new("higher\r\ncorrect\n"), &mut written);
+		assert_eq!(interaction.query(500).unwrap(), "higher");
+		assert_eq!(interaction.query(750).unwrap(), "correct");
+		assert_eq!(interaction.queries(), 2);
+		assert_eq!(interaction.read_line().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+		assert_eq!(written, b"500\n750\n");
+	}
+
+	#[test]
+	fn read_parses() {
+		let mut interaction = Interaction::new(Cursor::new("12\nx\n"), Vec::new());
+		assert_eq!(interaction.read::<usize>().unwrap(), 12);
+		assert_eq!(
+			interaction.read::<usize>().unwrap_err().kind(),
+			ErrorKind::InvalidData
+		);
+	}
+}
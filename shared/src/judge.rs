@@ -0,0 +1,632 @@
+//! Local judge: runs a solution binary against the samples of its problem.
+//! See `src/bin/judge.rs` for the command line side.
+
+use std::{
+	fmt::{Display, Formatter},
+	fs,
+	io::{self, BufRead, BufReader, Read, Write},
+	path::{Path, PathBuf},
+	process::{Child, Command, ExitStatus, Stdio},
+	sync::{
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+		Arc
+	},
+	thread,
+	time::{Duration, Instant}
+};
+
+use crate::verdict::Verdict;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+	Accepted,
+	WrongAnswer(String),
+	TimeLimitExceeded,
+	MemoryLimitExceeded,
+	QueryLimitExceeded,
+	RunTimeError(String),
+	JudgeError(String) // something wrong on our side, like a crashing interactor
+}
+impl Display for Outcome {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Outcome::Accepted => write!(f, "Accepted"),
+			Outcome::WrongAnswer(reason) => write!(f, "Wrong Answer: {}", reason),
+			Outcome::TimeLimitExceeded => write!(f, "Time Limit Exceeded"),
+			Outcome::MemoryLimitExceeded => write!(f, "Memory Limit Exceeded"),
+			Outcome::QueryLimitExceeded => write!(f, "Query Limit Exceeded"),
+			Outcome::RunTimeError(reason) => write!(f, "Run Time Error: {}", reason),
+			Outcome::JudgeError(reason) => write!(f, "Judge Error: {}", reason)
+		}
+	}
+}
+impl From<Verdict> for Outcome {
+	fn from(verdict: Verdict) -> Self {
+		match verdict {
+			Verdict::Accepted => Outcome::Accepted,
+			Verdict::WrongAnswer(reason) => Outcome::WrongAnswer(reason)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+	pub time:    Duration,
+	/// In MiB, of the address space of the solution (only enforced on unix).
+	pub memory:  Option<u64>,
+	pub queries: Option<usize> // only for interactive problems
+}
+impl Default for Limits {
+	fn default() -> Self {
+		Limits {
+			time:    Duration::from_secs(1),
+			memory:  None,
+			queries: None
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+	pub name:   String,
+	pub input:  PathBuf,
+	pub answer: PathBuf
+}
+
+/// Input/answer pairs in a samples directory, in the naming schemes used around the repo:
+/// `1.in`/`1.ans`, `in_1.txt`/`out_1.txt` and `in.txt`/`out.txt`.
+pub fn find_samples(directory: &Path) -> io::Result<Vec<Sample>> {
+	let mut samples = Vec::new();
+	for entry in fs::read_dir(directory)? {
+		let path = entry?.path();
+		let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+			continue;
+		};
+		let answer_name = if let Some(stem) = file_name.strip_suffix(".in") {
+			format!("{}.ans", stem)
+		} else if let Some(rest) = file_name.strip_prefix("in") {
+			match rest.strip_suffix(".txt") {
+				Some(rest) => format!("out{}.txt", rest),
+				None => continue
+			}
+		} else {
+			continue;
+		};
+		let answer = directory.join(answer_name);
+		if answer.is_file() {
+			samples.push(Sample {
+				name: file_name.to_string(),
+				input: path,
+				answer
+			});
+		}
+	}
+	samples.sort_by(|a, b| a.name.cmp(&b.name));
+	Ok(samples)
+}
+
+/// The default checker: same tokens in the same order, whitespace does not matter.
+/// Where the answer has a decimal number, the output may differ by 10^-6 (absolute or relative).
+pub fn compare(answer: &str, output: &str) -> Verdict {
+	let mut expected = answer.split_whitespace();
+	let mut actual = output.split_whitespace();
+	for index in 1.. {
+		match (expected.next(), actual.next()) {
+			(None, None) => return Verdict::Accepted,
+			(Some(expected), None) => {
+				return Verdict::WrongAnswer(format!(
+					"output ended early, expected `{}` as token {}",
+					expected, index
+				));
+			},
+			(None, Some(actual)) => {
+				return Verdict::WrongAnswer(format!(
+					"expected end of output, found `{}` as token {}",
+					actual, index
+				));
+			},
+			(Some(expected), Some(actual)) if !tokens_match(expected, actual) => {
+				return Verdict::WrongAnswer(format!(
+					"expected `{}`, found `{}` as token {}",
+					expected, actual, index
+				));
+			},
+			_ => {}
+		}
+	}
+	unreachable!()
+}
+
+fn tokens_match(expected: &str, actual: &str) -> bool {
+	if expected == actual {
+		return true;
+	}
+	if !expected.contains('.') {
+		return false;
+	}
+	match (expected.parse::<f64>(), actual.parse::<f64>()) {
+		(Ok(expected), Ok(actual)) => {
+			let difference = (expected - actual).abs();
+			difference <= 1e-6 || difference <= 1e-6 * expected.abs()
+		},
+		_ => false
+	}
+}
+
+/// Result of running one sample.
+#[derive(Debug, Clone)]
+pub struct Run {
+	pub outcome: Outcome,
+	pub time:    Duration,
+	pub stderr:  String
+}
+
+fn collect(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+	thread::spawn(move || {
+		let mut collected = Vec::new();
+		let _ = pipe.read_to_end(&mut collected);
+		String::from_utf8_lossy(&collected).into_owned()
+	})
+}
+
+/// Waits for every child, killing all of them once the deadline has passed.
+/// `None` if the deadline was hit.
+fn wait_all(children: &mut [&mut Child], deadline: Instant) -> Option<Vec<ExitStatus>> {
+	let mut statuses = vec![None; children.len()];
+	loop {
+		for (child, status) in children.iter_mut().zip(statuses.iter_mut()) {
+			if status.is_none() {
+				*status = child.try_wait().ok().flatten();
+			}
+		}
+		if statuses.iter().all(Option::is_some) {
+			return Some(statuses.into_iter().flatten().collect());
+		}
+		if Instant::now() >= deadline {
+			for child in children.iter_mut() {
+				let _ = child.kill();
+				let _ = child.wait();
+			}
+			return None;
+		}
+		thread::sleep(Duration::from_millis(1));
+	}
+}
+
+fn crash_reason(status: ExitStatus, stderr: &str) -> String {
+	let reason = match status.code() {
+		Some(code) => format!("exited with code {}", code),
+		None => killed_reason(status)
+	};
+	// rust aborts on a stack overflow, which looks like any other abort otherwise
+	if stderr.contains("has overflowed its stack") {
+		format!("stack overflow ({})", reason)
+	} else {
+		reason
+	}
+}
+
+#[cfg(unix)]
+fn killed_reason(status: ExitStatus) -> String {
+	use std::os::unix::process::ExitStatusExt;
+	match status.signal() {
+		Some(signal) => format!("killed by signal {}", signal),
+		None => "killed by a signal".to_string()
+	}
+}
+
+#[cfg(not(unix))]
+fn killed_reason(_status: ExitStatus) -> String {
+	"killed by a signal".to_string()
+}
+
+// rust aborts when an allocation fails, which under a memory limit means it ran out
+fn crashed(status: ExitStatus, stderr: &str, limits: Limits) -> Outcome {
+	if limits.memory.is_some() && stderr.contains("memory allocation of") {
+		Outcome::MemoryLimitExceeded
+	} else {
+		Outcome::RunTimeError(crash_reason(status, stderr))
+	}
+}
+
+/// The command run with its address space limited to `memory` MiB: a shell sets the limit
+/// with `ulimit`, then replaces itself with the command.
+#[cfg(unix)]
+fn limit_memory(command: &Command, memory: Option<u64>) -> Option<Command> {
+	let memory = memory?;
+	let mut limited = Command::new("sh");
+	limited
+		.arg("-c")
+		.arg(format!("ulimit -v {} && exec \"$0\" \"$@\"", memory * 1024))
+		.arg(command.get_program())
+		.args(command.get_args());
+	for (key, value) in command.get_envs() {
+		match value {
+			Some(value) => limited.env(key, value),
+			None => limited.env_remove(key)
+		};
+	}
+	if let Some(directory) = command.get_current_dir() {
+		limited.current_dir(directory);
+	}
+	Some(limited)
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_command: &Command, _memory: Option<u64>) -> Option<Command> {
+	None
+}
+
+/// How the output of a batch solution is checked.
+pub enum Checker<'a> {
+	/// Token by token against the answer, see `compare`.
+	Compare,
+	/// An output validator (see `validator`), already given its test case arguments.
+	Validator(&'a mut Command)
+}
+
+impl Checker<'_> {
+	fn check(self, answer: &str, output: &str) -> Outcome {
+		let validator = match self {
+			Checker::Compare => return compare(answer, output).into(),
+			Checker::Validator(validator) => validator
+		};
+		let child = validator
+			.stdin(Stdio::piped())
+			.stdout(Stdio::null())
+			.stderr(Stdio::piped())
+			.spawn();
+		let mut child = match child {
+			Ok(child) => child,
+			Err(e) => return Outcome::JudgeError(format!("could not start validator: {}", e))
+		};
+		// a validator may reject before reading everything
+		let _ = child.stdin.take().unwrap().write_all(output.as_bytes());
+		let result = match child.wait_with_output() {
+			Ok(result) => result,
+			Err(e) => return Outcome::JudgeError(format!("validator failed: {}", e))
+		};
+		let stderr = String::from_utf8_lossy(&result.stderr);
+		match Verdict::from_exit_code(result.status.code(), &stderr) {
+			Some(verdict) => verdict.into(),
+			None => Outcome::JudgeError(format!(
+				"validator {}: {}",
+				crash_reason(result.status, &stderr),
+				stderr.trim()
+			))
+		}
+	}
+}
+
+/// Runs a batch solution on the input, and checks its output.
+pub fn run_batch(
+	solution: &mut Command,
+	input: &str,
+	answer: &str,
+	checker: Checker,
+	limits: Limits
+) -> Run {
+	let mut limited = limit_memory(solution, limits.memory);
+	let solution = limited.as_mut().unwrap_or(solution);
+	let start = Instant::now();
+	let mut child = match solution
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+	{
+		Ok(child) => child,
+		Err(e) => {
+			return Run {
+				outcome: Outcome::JudgeError(format!("could not start solution: {}", e)),
+				time:    Duration::ZERO,
+				stderr:  String::new()
+			};
+		}
+	};
+	let mut stdin = child.stdin.take().unwrap();
+	let input = input.to_string();
+	// the solution may not read all of it, so a broken pipe is fine here
+	thread::spawn(move || {
+		let _ = stdin.write_all(input.as_bytes());
+	});
+	let stdout = collect(child.stdout.take().unwrap());
+	let stderr = collect(child.stderr.take().unwrap());
+
+	let status = wait_all(&mut [&mut child], start + limits.time);
+	let time = start.elapsed();
+	let output = stdout.join().unwrap_or_default();
+	let stderr = stderr.join().unwrap_or_default();
+	let outcome = match status {
+		None => Outcome::TimeLimitExceeded,
+		Some(statuses) if !statuses[0].success() => crashed(statuses[0], &stderr, limits),
+		Some(_) => checker.check(answer, &output)
+	};
+	Run {
+		outcome,
+		time,
+		stderr
+	}
+}
+
+/// Copies lines from one process to the other, flushing each of them,
+/// and counts them in `counter`.
+fn relay(
+	from: impl Read + Send + 'static,
+	mut to: impl Write + Send + 'static,
+	counter: Arc<AtomicUsize>,
+	stop: Arc<AtomicBool>
+) -> thread::JoinHandle<()> {
+	thread::spawn(move || {
+		let mut reader = BufReader::new(from);
+		let mut line = String::new();
+		while !stop.load(Ordering::Relaxed) {
+			line.clear();
+			match reader.read_line(&mut line) {
+				Ok(0) | Err(_) => break,
+				Ok(_) => {
+					counter.fetch_add(1, Ordering::Relaxed);
+					if to.write_all(line.as_bytes()).and_then(|_| to.flush()).is_err() {
+						break;
+					}
+				}
+			}
+		}
+		// dropping `to` closes the pipe, so the other side sees the end
+	})
+}
+
+/// Runs an interactive solution against an interactor, passing every line between them.
+/// The interactor decides the verdict (see `verdict`), the judge enforces the limits.
+pub fn run_interactive(solution: &mut Command, interactor: &mut Command, limits: Limits) -> Run {
+	let mut limited = limit_memory(solution, limits.memory);
+	let solution = limited.as_mut().unwrap_or(solution);
+	let start = Instant::now();
+	let spawn = |command: &mut Command| {
+		command
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()
+	};
+	let (mut solution, mut interactor) = match (spawn(solution), spawn(interactor)) {
+		(Ok(solution), Ok(interactor)) => (solution, interactor),
+		(solution, interactor) => {
+			for mut child in [solution, interactor].into_iter().flatten() {
+				let _ = child.kill();
+			}
+			return Run {
+				outcome: Outcome::JudgeError("could not start both processes".to_string()),
+				time:    Duration::ZERO,
+				stderr:  String::new()
+			};
+		}
+	};
+
+	let queries = Arc::new(AtomicUsize::new(0));
+	let stop = Arc::new(AtomicBool::new(false));
+	let to_interactor = relay(
+		solution.stdout.take().unwrap(),
+		interactor.stdin.take().unwrap(),
+		queries.clone(),
+		stop.clone()
+	);
+	let to_solution = relay(
+		interactor.stdout.take().unwrap(),
+		solution.stdin.take().unwrap(),
+		Arc::new(AtomicUsize::new(0)),
+		stop.clone()
+	);
+	let solution_stderr = collect(solution.stderr.take().unwrap());
+	let interactor_stderr = collect(interactor.stderr.take().unwrap());
+
+	let deadline = start + limits.time;
+	let mut over_query_limit = false;
+	let statuses = loop {
+		if let Some(limit) = limits.queries {
+			if queries.load(Ordering::Relaxed) > limit {
+				over_query_limit = true;
+				let _ = solution.kill();
+				let _ = interactor.kill();
+			}
+		}
+		let done = [solution.try_wait(), interactor.try_wait()]
+			.iter()
+			.all(|status| matches!(status, Ok(Some(_))));
+		if done || over_query_limit || Instant::now() >= deadline {
+			break wait_all(&mut [&mut solution, &mut interactor], deadline);
+		}
+		thread::sleep(Duration::from_millis(1));
+	};
+	let time = start.elapsed();
+	// the last query may have come in while checking the processes
+	over_query_limit |= limits
+		.queries
+		.is_some_and(|limit| queries.load(Ordering::Relaxed) > limit);
+	stop.store(true, Ordering::Relaxed);
+	let _ = to_interactor.join();
+	let _ = to_solution.join();
+	let stderr = solution_stderr.join().unwrap_or_default();
+	let interactor_stderr = interactor_stderr.join().unwrap_or_default();
+
+	let outcome = match statuses {
+		_ if over_query_limit => Outcome::QueryLimitExceeded,
+		None => Outcome::TimeLimitExceeded,
+		Some(statuses) => {
+			let (solution_status, interactor_status) = (statuses[0], statuses[1]);
+			match Verdict::from_exit_code(interactor_status.code(), &interactor_stderr) {
+				// a wrong answer from the interactor wins over a crash it caused
+				Some(Verdict::WrongAnswer(reason)) => Outcome::WrongAnswer(reason),
+				_ if !solution_status.success() => crashed(solution_status, &stderr, limits),
+				Some(Verdict::Accepted) => Outcome::Accepted,
+				None => Outcome::JudgeError(format!(
+					"interactor {}: {}",
+					crash_reason(interactor_status, &interactor_stderr),
+					interactor_stderr.trim()
+				))
+			}
+		}
+	};
+	Run {
+		outcome,
+		time,
+		stderr
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{path::Path, process::Command, time::Duration};
+
+	use crate::{
+		judge::{compare, find_samples, run_batch, run_interactive, Checker, Limits, Outcome},
+		verdict::Verdict
+	};
+
+	#[test]
+	fn compare_ignores_whitespace() {
+		assert_eq!(compare("4 7 10805\n", "4  7\r\n10805"), Verdict::Accepted);
+		assert!(matches!(compare("4 7", "4 8"), Verdict::WrongAnswer(_)));
+		assert!(matches!(compare("4 7", "4"), Verdict::WrongAnswer(_)));
+		assert!(matches!(compare("4", "4 7"), Verdict::WrongAnswer(_)));
+	}
+
+	#[test]
+	fn compare_floats_with_tolerance() {
+		assert_eq!(compare("24.75", "24.7500001"), Verdict::Accepted);
+		assert_eq!(compare("4.0", "4"), Verdict::Accepted);
+		assert!(matches!(compare("0.0740740740740741", "0.075"), Verdict::WrongAnswer(_)));
+		// integers are compared exactly
+		assert!(matches!(compare("10", "10.0"), Verdict::WrongAnswer(_)));
+	}
+
+	#[test]
+	fn samples_in_every_naming_scheme() {
+		let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+		let names = |path: &str| {
+			find_samples(&root.join(path))
+				.unwrap()
+				.into_iter()
+				.map(|sample| sample.name)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(names("kattis/knightjump/src"), vec!["1.in", "2.in"]);
+		assert_eq!(names("kattis/wheresmyinternet/src"), vec![
+			"in_1.txt", "in_2.txt", "in_3.txt"
+		]);
+		assert_eq!(names("kattis/reachableroads/src"), vec!["in.txt"]);
+		assert_eq!(names("kattis/challenges/alien-numerals/src"), vec!["sample.in"]);
+	}
+
+	#[cfg(unix)]
+	fn shell(script: &str) -> Command {
+		let mut command = Command::new("sh");
+		command.args(["-c", script]);
+		command
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn batch_outcomes() {
+		let limits = Limits {
+			time: Duration::from_millis(500),
+			..Limits::default()
+		};
+		let add = "read a b; echo $((a + b))";
+		let run = run_batch(&mut shell(add), "1 2\n", "3\n", Checker::Compare, limits);
+		assert_eq!(run.outcome, Outcome::Accepted);
+		let run = run_batch(&mut shell("echo 4"), "1 2\n", "3\n", Checker::Compare, limits);
+		assert!(matches!(run.outcome, Outcome::WrongAnswer(_)));
+		let run = run_batch(&mut shell("echo 3; exit 1"), "", "3\n", Checker::Compare, limits);
+		assert!(matches!(run.outcome, Outcome::RunTimeError(_)));
+		let run = run_batch(&mut shell("exec sleep 5"), "", "3\n", Checker::Compare, limits);
+		assert_eq!(run.outcome, Outcome::TimeLimitExceeded);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn stack_overflow_is_told_apart() {
+		let overflow = "echo \"thread 'main' has overflowed its stack\" >&2; kill -ABRT $$";
+		let run = run_batch(&mut shell(overflow), "", "", Checker::Compare, Limits::default());
+		assert_eq!(
+			run.outcome,
+			Outcome::RunTimeError("stack overflow (killed by signal 6)".to_string())
+		);
+		let run = run_batch(&mut shell("kill -ABRT $$"), "", "", Checker::Compare, Limits::default());
+		assert_eq!(run.outcome, Outcome::RunTimeError("killed by signal 6".to_string()));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn memory_limit() {
+		let limits = Limits {
+			memory: Some(64),
+			..Limits::default()
+		};
+		// the limit is set in KiB, and the arguments still get through the shell around it
+		let mut limit = shell("ulimit -v; echo \"$0\"");
+		limit.arg("'quoted argument'");
+		let run = run_batch(&mut limit, "", "65536 'quoted argument'", Checker::Compare, limits);
+		assert_eq!(run.outcome, Outcome::Accepted);
+		let out_of_memory = "echo 'memory allocation of 1048576 bytes failed' >&2; kill -ABRT $$";
+		let run = run_batch(&mut shell(out_of_memory), "", "", Checker::Compare, limits);
+		assert_eq!(run.outcome, Outcome::MemoryLimitExceeded);
+		// without a limit it is just a crash
+		let run = run_batch(&mut shell(out_of_memory), "", "", Checker::Compare, Limits::default());
+		assert_eq!(run.outcome, Outcome::RunTimeError("killed by signal 6".to_string()));
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn batch_with_validator() {
+		// accepts any output with an odd number
+		let odd = "read n; [ $((n % 2)) = 1 ] && exit 42; echo \"$n is even\" >&2; exit 43";
+		let limits = Limits::default();
+		let validate = |output: &str, validator: &str| {
+			let mut validator = shell(validator);
+			let checker = Checker::Validator(&mut validator);
+			run_batch(&mut shell(output), "", "3\n", checker, limits).outcome
+		};
+		assert_eq!(validate("echo 5", odd), Outcome::Accepted);
+		assert_eq!(validate("echo 4", odd), Outcome::WrongAnswer("4 is even".to_string()));
+		let outcome = validate("echo 3", "exit 1");
+		assert!(matches!(outcome, Outcome::JudgeError(_)));
+	}
+
+	// interactor thinks of 3, answers "higher"/"lower"/"correct"
+	#[cfg(unix)]
+	const INTERACTOR: &str = "while read guess; do \
+		if [ $guess -lt 3 ]; then echo higher; \
+		elif [ $guess -gt 3 ]; then echo lower; \
+		else echo correct; exit 42; fi; done; echo 'no guess' >&2; exit 43";
+
+	#[cfg(unix)]
+	#[test]
+	fn interactive_outcomes() {
+		let limits = Limits {
+			time: Duration::from_secs(2),
+			queries: Some(3),
+			..Limits::default()
+		};
+		let counting_up = "i=1; while true; do echo $i; read reply; \
+			[ $reply = correct ] && exit 0; i=$((i + 1)); done";
+		let run = run_interactive(&mut shell(counting_up), &mut shell(INTERACTOR), limits);
+		assert_eq!(run.outcome, Outcome::Accepted);
+
+		let limits = Limits {
+			queries: Some(2),
+			..limits
+		};
+		let run = run_interactive(&mut shell(counting_up), &mut shell(INTERACTOR), limits);
+		assert_eq!(run.outcome, Outcome::QueryLimitExceeded);
+
+		let run = run_interactive(&mut shell("exit 0"), &mut shell(INTERACTOR), limits);
+		assert_eq!(run.outcome, Outcome::WrongAnswer("no guess".to_string()));
+
+		let run = run_interactive(&mut shell("exec sleep 5"), &mut shell(INTERACTOR), Limits {
+			time: Duration::from_millis(300),
+			..limits
+		});
+		assert_eq!(run.outcome, Outcome::TimeLimitExceeded);
+	}
+}
@@ -0,0 +1,59 @@
+//! Shared helpers for the solutions in this repo.
+//!
+//! # Input layouts
+//! `#[derive(KattisInput)]` on a struct with named fields reads the fields in order.
+//! Values are whitespace separated and stay on the current line unless told otherwise,
+//! using `#[kattis(...)]` on the field:
+//! - `newline`: the field starts on the next line.
+//! - `line`: the whole (rest of the) line is one value, parsed with `FromStr`.
+//! - `count = expr`: a `Vec` with exactly `expr` elements, usually an earlier field.
+//! - `rest`: a `Vec` with elements until the end of the input.
+//! - `per_line`: every element of a `Vec` starts on the next line.
+//! - `grid(rows = expr, cols = expr)`: a `Vec<Vec<T>>` of characters, one row per line
+//!   (`cols` is optional, `T: TryFrom<char>`).
+//!
+//! `per_line` and `grid` start every row on the next line themselves, so they don't take
+//! `newline` as well:
+//! ```compile_fail
+//! #[derive(shared::KattisInput)]
+//! struct Board {
+//!     n:    usize,
+//!     #[kattis(newline, grid(rows = n, cols = n))]
+//!     grid: Vec<Vec<char>>
+//! }
+//! ```
+//!
+//! A `Vec` without `count` or `rest` takes the elements until the end of the current line.
+//! The derive also implements `FromStr`, which requires the whole input to be consumed.
+//!
+//! # Output layouts
+//! `#[derive(KattisOutput)]` is the counterpart for solutions, see [`output`].
+
+// lets the derive macros refer to `::shared` from inside this crate as well
+extern crate self as shared;
+
+pub mod cases;
+pub mod diagnostics;
+pub mod dot;
+pub mod driver;
+pub mod fuzz;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod interactive;
+pub mod judge;
+pub mod metadata;
+pub mod normalize;
+pub mod output;
+pub mod profile;
+pub mod property;
+pub mod random;
+pub mod scanner;
+pub mod search;
+pub mod solvers;
+pub mod validator;
+pub mod verdict;
+pub mod visual;
+
+pub use input::KattisInput;
+pub use shared_derive::{KattisInput, KattisOutput};
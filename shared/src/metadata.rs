@@ -0,0 +1,173 @@
+//! What we know about a problem besides its solution, in a `problem.yml` next to its Cargo.toml:
+//! ```text
+//! ---
+//! source: https://open.kattis.com/problems/knightjump
+//! contest: ~
+//! time_limit: 1
+//! memory_limit: 1024
+//! difficulty: 2.1
+//! tags: [bfs, grid]
+//! ```
+//! Limits are in seconds and MiB, `~` is unknown. The judge takes its limits from here,
+//! and the `index` binary searches all problems by tag and difficulty.
+
+use std::{
+	fs,
+	path::{Path, PathBuf},
+	str::FromStr,
+	time::Duration
+};
+
+pub const FILE: &str = "problem.yml";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+	pub source:       Option<String>,
+	pub contest:      Option<String>,
+	pub time_limit:   Option<Duration>,
+	/// In MiB.
+	pub memory_limit: Option<u64>,
+	pub difficulty:   Option<f64>,
+	pub tags:         Vec<String>
+}
+
+impl Metadata {
+	/// The metadata of the problem in the directory, `None` if it has no metadata file.
+	pub fn read(problem: &Path) -> Option<Result<Metadata, String>> {
+		let content = fs::read_to_string(problem.join(FILE)).ok()?;
+		Some(content.parse())
+	}
+
+	pub fn has_tag(&self, tag: &str) -> bool {
+		self.tags.iter().any(|own| own.eq_ignore_ascii_case(tag))
+	}
+}
+
+fn known(value: &str) -> Option<&str> {
+	match value {
+		"" | "~" => None,
+		value => Some(value)
+	}
+}
+
+fn number<T: FromStr>(key: &str, value: &str) -> Result<Option<T>, String> {
+	known(value)
+		.map(|value| value.parse().map_err(|_| format!("`{}` is not a valid {}", value, key)))
+		.transpose()
+}
+
+impl FromStr for Metadata {
+	type Err = String;
+
+	fn from_str(content: &str) -> Result<Self, Self::Err> {
+		let mut metadata = Metadata::default();
+		for line in content.lines().map(str::trim) {
+			if line.is_empty() || line == "---" || line.starts_with('#') {
+				continue;
+			}
+			let (key, value) = line
+				.split_once(':')
+				.ok_or_else(|| format!("expected `key: value`, found `{}`", line))?;
+			let value = value.trim();
+			match key.trim() {
+				"source" => metadata.source = known(value).map(str::to_string),
+				"contest" => metadata.contest = known(value).map(str::to_string),
+				"time_limit" => {
+					metadata.time_limit = number::<f64>("time limit", value)?
+						.filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+						.map(Duration::from_secs_f64)
+				},
+				"memory_limit" => metadata.memory_limit = number("memory limit", value)?,
+				"difficulty" => metadata.difficulty = number("difficulty", value)?,
+				"tags" => {
+					let list = value
+						.strip_prefix('[')
+						.and_then(|list| list.strip_suffix(']'))
+						.ok_or_else(|| format!("tags should be a list like [bfs, grid]: {}", value))?;
+					metadata.tags = list
+						.split(',')
+						.map(str::trim)
+						.filter(|tag| !tag.is_empty())
+						.map(str::to_string)
+						.collect();
+				},
+				other => return Err(format!("unknown key `{}`", other))
+			}
+		}
+		Ok(metadata)
+	}
+}
+
+/// Every problem crate below the directory (a directory with a `Cargo.toml` and a `src/main.rs`),
+/// sorted by path. The `shared` crate itself and build output are skipped.
+pub fn find_problems(root: &Path) -> Vec<PathBuf> {
+	let mut problems = Vec::new();
+	let mut directories = vec![root.to_path_buf()];
+	while let Some(directory) = directories.pop() {
+		if directory.join("Cargo.toml").is_file() && directory.join("src/main.rs").is_file() {
+			problems.push(directory.clone());
+		}
+		let Ok(entries) = fs::read_dir(&directory) else {
+			continue;
+		};
+		for entry in entries.flatten() {
+			let path = entry.path();
+			let name = entry.file_name();
+			let skipped = ["target", "shared", "src"].iter().any(|skipped| name == *skipped)
+				|| name.to_string_lossy().starts_with('.');
+			if path.is_dir() && !skipped {
+				directories.push(path);
+			}
+		}
+	}
+	problems.sort();
+	problems
+}
+
+#[cfg(test)]
+mod tests {
+	use std::{path::Path, time::Duration};
+
+	use crate::metadata::{find_problems, Metadata};
+
+	#[test]
+	fn parses_metadata() {
+		let metadata: Metadata = "---\nsource: https://open.kattis.com/problems/knightjump\n\
+		                          contest: ~\ntime_limit: 1.5\nmemory_limit: 1024\n\
+		                          difficulty: ~\ntags: [BFS, grid]\n"
+			.parse()
+			.unwrap();
+		assert_eq!(metadata, Metadata {
+			source:       Some("https://open.kattis.com/problems/knightjump".to_string()),
+			contest:      None,
+			time_limit:   Some(Duration::from_millis(1500)),
+			memory_limit: Some(1024),
+			difficulty:   None,
+			tags:         vec!["BFS".to_string(), "grid".to_string()]
+		});
+		assert!(metadata.has_tag("bfs"));
+		assert!(!metadata.has_tag("dsu"));
+	}
+
+	#[test]
+	fn rejects_bad_metadata() {
+		assert!("time_limit: fast".parse::<Metadata>().is_err());
+		assert!("tags: bfs".parse::<Metadata>().is_err());
+		assert!("difficulty 3".parse::<Metadata>().is_err());
+		assert!("author: me".parse::<Metadata>().is_err());
+		assert_eq!("tags: []".parse::<Metadata>().unwrap().tags, Vec::<String>::new());
+	}
+
+	#[test]
+	fn every_problem_has_valid_metadata() {
+		let problems = find_problems(Path::new(".."));
+		assert!(problems.len() > 10, "{:?}", problems);
+		for problem in problems {
+			match Metadata::read(&problem) {
+				Some(Ok(_)) => {},
+				Some(Err(e)) => panic!("{}: {}", problem.display(), e),
+				None => panic!("{} has no {}", problem.display(), crate::metadata::FILE)
+			}
+		}
+	}
+}
@@ -0,0 +1,121 @@
+//! Cleans up an input before it is parsed, so a sample saved by an editor on another OS
+//! parses the same as the original.
+//!
+//! A byte order mark at the start is always dropped, and `\r\n` (or a lone `\r`) becomes `\n`.
+//! Where whitespace is insignificant, which it is for most problems, trailing whitespace is
+//! also removed from every line and the input ends with exactly one newline.
+//! `input::parse` (so every derived parser) does the first part,
+//! the driver does all of it unless the settings of the problem say otherwise.
+//! Solutions with their own parser clean up in there, and check it with `assert_same_when_messy`.
+
+use std::fmt::Debug;
+
+/// Whether spaces at the end of a line, and blank lines at the end of the input, mean something.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Whitespace {
+	#[default]
+	Insignificant,
+	/// Every space counts, like in some problems about text.
+	Significant
+}
+
+pub fn normalize(input: &str, whitespace: Whitespace) -> String {
+	let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+	let unified = input.replace("\r\n", "\n").replace('\r', "\n");
+	match whitespace {
+		Whitespace::Significant => unified,
+		Whitespace::Insignificant => {
+			let mut lines: Vec<&str> = unified.lines().map(str::trim_end).collect();
+			while lines.last().is_some_and(|line| line.is_empty()) {
+				lines.pop();
+			}
+			lines.iter().map(|line| format!("{}\n", line)).collect()
+		}
+	}
+}
+
+/// The input as another editor might save it: with a byte order mark and CRLF line endings,
+/// and where whitespace is insignificant with trailing spaces and blank lines at the end.
+/// For tests of parsers that should not care.
+pub fn messy(input: &str, whitespace: Whitespace) -> String {
+	let mut messy = "\u{feff}".to_string();
+	for line in input.lines() {
+		messy.push_str(line);
+		if whitespace == Whitespace::Insignificant {
+			messy.push_str(" \t ");
+		}
+		messy.push_str("\r\n");
+	}
+	if whitespace == Whitespace::Insignificant {
+		messy.push_str("\r\n  \r\n");
+	}
+	messy
+}
+
+/// Checks that `solve` gives the same for `input` as for its `messy` version.
+/// The `whitespace` is what the problem allows, like the driver settings say for driver solutions.
+#[track_caller]
+pub fn assert_same_when_messy<T, E>(
+	input: &str,
+	whitespace: Whitespace,
+	solve: impl Fn(&str) -> Result<T, E>
+) where
+	T: PartialEq + Debug,
+	E: Debug
+{
+	let expected = solve(input).expect("Cannot solve the input!");
+	let messy = messy(input, whitespace);
+	match solve(&messy) {
+		Ok(actual) => assert_eq!(actual, expected, "for the messy input {:?}", messy),
+		Err(e) => panic!("Cannot solve the messy input {:?}: {:?}", messy, e)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::panic;
+
+	use crate::normalize::{assert_same_when_messy, messy, normalize, Whitespace};
+
+	#[test]
+	fn line_endings_and_byte_order_mark() {
+		let input = "\u{feff}3 4\r\nab  \rc\n";
+		assert_eq!(normalize(input, Whitespace::Significant), "3 4\nab  \nc\n");
+		assert_eq!(normalize(input, Whitespace::Insignificant), "3 4\nab\nc\n");
+	}
+
+	#[test]
+	fn final_newline() {
+		assert_eq!(normalize("1 2", Whitespace::Insignificant), "1 2\n");
+		assert_eq!(normalize("1 2\n\n \n\t", Whitespace::Insignificant), "1 2\n");
+		assert_eq!(normalize("1 2\n\n", Whitespace::Significant), "1 2\n\n");
+		assert_eq!(normalize("", Whitespace::Insignificant), "");
+	}
+
+	#[test]
+	fn messy_round_trip() {
+		let input = "2\nhello world\nbye\n";
+		for whitespace in [Whitespace::Insignificant, Whitespace::Significant] {
+			let messy = messy(input, whitespace);
+			assert_ne!(messy, input);
+			assert_eq!(normalize(&messy, whitespace).trim_end(), input.trim_end());
+		}
+	}
+
+	#[test]
+	fn same_when_messy() {
+		let words = |input: &str| -> Result<Vec<String>, String> {
+			let input = input.trim_start_matches('\u{feff}');
+			Ok(input.split_whitespace().map(str::to_string).collect())
+		};
+		assert_same_when_messy("2\nhello world\n", Whitespace::Insignificant, words);
+		// whole lines keep the byte order mark, the trailing spaces and the `\r`
+		let lines = |input: &str| -> Result<Vec<String>, String> {
+			Ok(input.lines().map(str::to_string).collect())
+		};
+		assert!(panic::catch_unwind(|| {
+			assert_same_when_messy("2\nhello world\n", Whitespace::Insignificant, lines)
+		})
+		.is_err());
+	}
+}
@@ -0,0 +1,234 @@
+//! Reading solutions back from `.ans` files.
+//! `#[derive(KattisOutput)]` implements `Display` for the solution, plus (in tests only)
+//! a parser for the same layout, so a sample answer can be compared as a value:
+//! - fields are separated by a space, or a newline with `#[kattis(newline)]`.
+//! - a `Vec` is separated by spaces, or one element per line with `#[kattis(per_line)]`.
+//!   An empty one writes nothing, not even the space before it.
+//! - `#[kattis(precision = 6)]` writes floats with that many decimals.
+//! - enum variants are either a unit variant written as `#[kattis(literal = "...")]`,
+//!   or variants whose fields are written like a struct's.
+
+use crate::scanner::{ParseError, Scanner};
+
+/// Runs `read` on a copy of the scanner, and only moves the scanner along if it succeeded.
+pub fn attempt<'a, T>(
+	scanner: &mut Scanner<'a>,
+	read: impl FnOnce(&mut Scanner<'a>) -> Result<T, ParseError>
+) -> Option<T> {
+	let mut attempt = scanner.clone();
+	let value = read(&mut attempt).ok()?;
+	*scanner = attempt;
+	Some(value)
+}
+
+/// Expects the words of `literal`, with any amount of inline whitespace between them.
+pub fn literal(scanner: &mut Scanner<'_>, literal: &str) -> Result<(), ParseError> {
+	for word in literal.split_whitespace() {
+		let start = scanner.position();
+		let token = scanner.token_str()?;
+		if token != word {
+			return Err(scanner.error_at(
+				start,
+				format!("expected `{}`, found `{}`", literal, token)
+			));
+		}
+	}
+	Ok(())
+}
+
+/// One element on the current line, then one per line until the end of the input.
+pub fn read_lines<'a, T>(
+	scanner: &mut Scanner<'a>,
+	mut element: impl FnMut(&mut Scanner<'a>) -> Result<T, ParseError>
+) -> Result<Vec<T>, ParseError> {
+	let mut elements = Vec::new();
+	while !scanner.at_end() {
+		if !elements.is_empty() {
+			scanner.end_line()?;
+		}
+		elements.push(element(scanner)?);
+	}
+	Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{scanner::Position, KattisOutput};
+
+	// refrigerator
+	#[derive(Debug, PartialEq, KattisOutput)]
+	struct Trips {
+		car_a_trips: usize,
+		car_b_trips: usize,
+		total_cost:  usize
+	}
+
+	// spritt
+	#[derive(Debug, PartialEq, KattisOutput)]
+	enum Sanitizer {
+		#[kattis(literal = "Jebb")]
+		Jebb,
+		#[kattis(literal = "Neibb")]
+		Neibb
+	}
+
+	// knightjump
+	#[derive(Debug, PartialEq, KattisOutput)]
+	enum Jumps {
+		Steps(usize),
+		#[kattis(literal = "-1")]
+		Unsolvable
+	}
+
+	// theseus
+	#[derive(Debug, PartialEq, KattisOutput)]
+	enum Theseus {
+		#[kattis(literal = "paradox avoided")]
+		ParadoxAvoided,
+		DayOfReplace(usize)
+	}
+
+	// wheresmyinternet
+	#[derive(Debug, PartialEq, KattisOutput)]
+	enum Internet {
+		#[kattis(literal = "Connected")]
+		Connected,
+		Missing(#[kattis(per_line)] Vec<usize>)
+	}
+
+	// metronome
+	#[derive(Debug, PartialEq, KattisOutput)]
+	struct Revolutions(#[kattis(precision = 2)] f32);
+
+	// spam-detection
+	#[derive(Debug, PartialEq, KattisOutput)]
+	struct Ratios {
+		#[kattis(per_line, precision = 6)]
+		ratios: Vec<f64>
+	}
+
+	#[derive(Debug, PartialEq, KattisOutput)]
+	struct Mixed {
+		count:  usize,
+		values: Vec<i64>,
+		#[kattis(newline)]
+		name:   String
+	}
+
+	#[test]
+	fn refrigerator_sample() {
+		let answer = include_str!("../../kattis/refrigerator/src/1.ans");
+		let trips: Trips = answer.parse().unwrap();
+		assert_eq!(trips, Trips {
+			car_a_trips: 4,
+			car_b_trips: 7,
+			total_cost:  10805
+		});
+		assert_eq!(trips.to_string(), answer.trim());
+	}
+
+	#[test]
+	fn refrigerator_missing_value() {
+		let error = "4 7".parse::<Trips>().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 4 });
+	}
+
+	#[test]
+	fn spritt_samples() {
+		let answer = include_str!("../../kattis/spritt/src/1.ans");
+		assert_eq!(answer.parse(), Ok(Sanitizer::Jebb));
+		let answer = include_str!("../../kattis/spritt/src/2.ans");
+		assert_eq!(answer.parse(), Ok(Sanitizer::Neibb));
+		assert_eq!(Sanitizer::Neibb.to_string(), "Neibb");
+		assert!("Jeb".parse::<Sanitizer>().is_err());
+	}
+
+	#[test]
+	fn knightjump_samples() {
+		let answer = include_str!("../../kattis/knightjump/src/1.ans");
+		assert_eq!(answer.parse(), Ok(Jumps::Steps(2)));
+		let answer = include_str!("../../kattis/knightjump/src/2.ans");
+		assert_eq!(answer.parse(), Ok(Jumps::Unsolvable));
+		assert_eq!(Jumps::Unsolvable.to_string(), "-1");
+		assert_eq!(Jumps::Steps(7).to_string(), "7");
+	}
+
+	#[test]
+	fn theseus_samples() {
+		let answer = include_str!("../../kattis/challenges/theseus/src/out_1.txt");
+		assert_eq!(answer.parse(), Ok(Theseus::DayOfReplace(4)));
+		let answer = include_str!("../../kattis/challenges/theseus/src/out_2.txt");
+		assert_eq!(answer.parse(), Ok(Theseus::ParadoxAvoided));
+		assert_eq!(Theseus::ParadoxAvoided.to_string(), answer.trim());
+	}
+
+	#[test]
+	fn wheresmyinternet_samples() {
+		let answer = include_str!("../../kattis/wheresmyinternet/src/out_3.txt");
+		let missing: Internet = answer.parse().unwrap();
+		assert_eq!(missing, Internet::Missing(vec![2, 3, 4]));
+		assert_eq!(missing.to_string(), answer.trim());
+		let answer = include_str!("../../kattis/wheresmyinternet/src/out_2.txt");
+		assert_eq!(answer.parse(), Ok(Internet::Connected));
+	}
+
+	#[test]
+	fn float_precision() {
+		assert_eq!(Revolutions(24.75).to_string(), "24.75");
+		assert_eq!(Revolutions(4.0).to_string(), "4.00");
+		let answer = include_str!("../../kattis/metronome/src/metronome-0000.ans");
+		assert_eq!(answer.parse(), Ok(Revolutions(4.0)));
+
+		let answer = include_str!("../../kattis/challenges/spam-detection/src/out_1.txt");
+		let ratios: Ratios = answer.parse().unwrap();
+		assert_eq!(ratios.ratios.len(), 4);
+		assert_eq!(
+			ratios.to_string(),
+			"0.074074\n0.666667\n0.222222\n0.037037"
+		);
+	}
+
+	#[test]
+	fn fields_and_lists() {
+		let mixed = Mixed {
+			count:  3,
+			values: vec![-1, 2, 3],
+			name:   "mixed".to_string()
+		};
+		assert_eq!(mixed.to_string(), "3 -1 2 3\nmixed");
+		assert_eq!(mixed.to_string().parse(), Ok(mixed));
+	}
+
+	#[derive(Debug, PartialEq, KattisOutput)]
+	struct Around {
+		before: Vec<u8>,
+		middle: u8,
+		after:  Vec<u8>
+	}
+
+	#[test]
+	fn empty_lists() {
+		let mixed = Mixed {
+			count:  0,
+			values: Vec::new(),
+			name:   "empty".to_string()
+		};
+		assert_eq!(mixed.to_string(), "0\nempty");
+		assert_eq!(mixed.to_string().parse(), Ok(mixed));
+
+		let around = Around {
+			before: Vec::new(),
+			middle: 5,
+			after:  Vec::new()
+		};
+		assert_eq!(around.to_string(), "5");
+		let around = Around {
+			before: vec![1],
+			middle: 5,
+			after:  vec![6, 7]
+		};
+		assert_eq!(around.to_string(), "1 5 6 7");
+		assert_eq!(Internet::Missing(Vec::new()).to_string(), "");
+		assert_eq!(Ratios { ratios: Vec::new() }.to_string(), "");
+	}
+}
@@ -0,0 +1,276 @@
+//! Where the time (and memory) of a run goes, per phase.
+//!
+//! Profiling is opt-in: only with the `PROFILE_REPORT` environment variable set does the driver
+//! measure every phase, and it writes the profile to the file named there
+//! (the local judge sets it per sample with `--profile`).
+//! Only solutions running through `shared::driver::run` are profiled,
+//! solutions with their own `main` ignore the variable.
+//! Allocations are only counted in binaries using the counting allocator:
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: shared::profile::CountingAllocator = shared::profile::CountingAllocator;
+//! ```
+
+use std::{
+	alloc::{GlobalAlloc, Layout, System},
+	fmt::{Display, Formatter},
+	str::FromStr,
+	sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+	time::{Duration, Instant}
+};
+
+use crate::diagnostics::Phase;
+
+pub const REPORT_VARIABLE: &str = "PROFILE_REPORT";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting every allocation and the bytes asked for.
+/// Reallocations count as an allocation of the new size.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		count(layout.size());
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		count(layout.size());
+		unsafe { System.alloc_zeroed(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(ptr, layout) }
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		count(new_size);
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+fn count(bytes: usize) {
+	ACTIVE.store(true, Ordering::Relaxed);
+	ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+	BYTES.fetch_add(bytes, Ordering::Relaxed);
+}
+
+/// Allocations so far in the whole process, `None` without the counting allocator.
+pub fn allocations() -> Option<(usize, usize)> {
+	ACTIVE.load(Ordering::Relaxed).then(|| {
+		(
+			ALLOCATIONS.load(Ordering::Relaxed),
+			BYTES.load(Ordering::Relaxed)
+		)
+	})
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseProfile {
+	pub phase:       Phase,
+	pub time:        Duration,
+	pub allocations: Option<usize>,
+	pub bytes:       Option<usize>
+}
+
+/// Measurements of the phases of one run, in order.
+/// Written as one `phase seconds allocations bytes` line per phase, `-` for unknown counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+	pub phases: Vec<PhaseProfile>
+}
+
+/// Measures phases one after another.
+pub struct Profiler {
+	profile:     Profile,
+	start:       Instant,
+	allocations: Option<(usize, usize)>
+}
+
+impl Profiler {
+	pub fn start() -> Self {
+		Profiler {
+			profile:     Profile::default(),
+			start:       Instant::now(),
+			allocations: allocations()
+		}
+	}
+
+	/// Ends the phase that started at the previous call (or at `start`).
+	pub fn end(&mut self, phase: Phase) {
+		let now = Instant::now();
+		let allocations = allocations();
+		let difference = self
+			.allocations
+			.zip(allocations)
+			.map(|((count, bytes), (new_count, new_bytes))| (new_count - count, new_bytes - bytes));
+		self.profile.phases.push(PhaseProfile {
+			phase,
+			time: now - self.start,
+			allocations: difference.map(|(count, _)| count),
+			bytes: difference.map(|(_, bytes)| bytes)
+		});
+		self.start = now;
+		self.allocations = allocations;
+	}
+
+	pub fn finish(self) -> Profile {
+		self.profile
+	}
+}
+
+impl Profile {
+	/// Adds up the phases of several runs, like all samples of a problem.
+	pub fn total<'a>(profiles: impl IntoIterator<Item = &'a Profile>) -> Profile {
+		let mut total = Profile::default();
+		for profile in profiles {
+			for phase in profile.phases.iter() {
+				match total.phases.iter_mut().find(|total| total.phase == phase.phase) {
+					Some(total) => {
+						total.time += phase.time;
+						total.allocations = total.allocations.zip(phase.allocations).map(|(a, b)| a + b);
+						total.bytes = total.bytes.zip(phase.bytes).map(|(a, b)| a + b);
+					},
+					None => total.phases.push(*phase)
+				}
+			}
+		}
+		total
+	}
+}
+
+fn optional(count: Option<usize>) -> String {
+	count.map_or("-".to_string(), |count| count.to_string())
+}
+
+impl Display for Profile {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		for phase in self.phases.iter() {
+			writeln!(
+				f,
+				"{} {:.6} {} {}",
+				phase.phase,
+				phase.time.as_secs_f64(),
+				optional(phase.allocations),
+				optional(phase.bytes)
+			)?;
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Profile {
+	type Err = String;
+
+	fn from_str(report: &str) -> Result<Self, Self::Err> {
+		let phases = report
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.map(|line| {
+				let parts: Vec<&str> = line.split_whitespace().collect();
+				let [phase, seconds, allocations, bytes] = parts[..] else {
+					return Err(format!("expected 4 values in `{}`", line));
+				};
+				let optional = |count: &str| -> Result<Option<usize>, String> {
+					match count {
+						"-" => Ok(None),
+						count => count.parse().map(Some).map_err(|e| format!("{}", e))
+					}
+				};
+				Ok(PhaseProfile {
+					phase:       match phase {
+						"parsing" => Phase::Parsing,
+						"solving" => Phase::Solving,
+						"formatting" => Phase::Formatting,
+						other => return Err(format!("unknown phase `{}`", other))
+					},
+					time:        seconds
+						.parse()
+						.map_err(|e| format!("{}", e))
+						.and_then(|seconds| {
+							// negative, NaN or too big
+							Duration::try_from_secs_f64(seconds)
+								.map_err(|e| format!("{} seconds: {}", seconds, e))
+						})?,
+					allocations: optional(allocations)?,
+					bytes:       optional(bytes)?
+				})
+			})
+			.collect::<Result<_, _>>()?;
+		Ok(Profile { phases })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::{
+		diagnostics::Phase,
+		profile::{allocations, CountingAllocator, PhaseProfile, Profile, Profiler}
+	};
+
+	#[global_allocator]
+	static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+	fn phase(phase: Phase, millis: u64, allocations: Option<usize>) -> PhaseProfile {
+		PhaseProfile {
+			phase,
+			time: Duration::from_millis(millis),
+			allocations,
+			bytes: allocations.map(|count| count * 8)
+		}
+	}
+
+	#[test]
+	fn counts_allocations() {
+		let mut profiler = Profiler::start();
+		let numbers: Vec<u64> = std::hint::black_box((0..1000).collect());
+		profiler.end(Phase::Solving);
+		let profile = profiler.finish();
+		// other tests allocate at the same time, so only a lower bound is known
+		assert!(profile.phases[0].allocations.unwrap() >= 1);
+		assert!(profile.phases[0].bytes.unwrap() >= 8 * numbers.len());
+		assert!(allocations().is_some());
+	}
+
+	#[test]
+	fn report_round_trip() {
+		let profile = Profile {
+			phases: vec![
+				phase(Phase::Parsing, 12, Some(3)),
+				phase(Phase::Solving, 1500, None),
+			]
+		};
+		let report = profile.to_string();
+		assert_eq!(report, "parsing 0.012000 3 24\nsolving 1.500000 - -\n");
+		assert_eq!(report.parse::<Profile>().unwrap(), profile);
+		assert!("parsing 1".parse::<Profile>().is_err());
+		assert!("reading 1 - -".parse::<Profile>().is_err());
+		for time in ["-1", "NaN", "inf", "1e30"] {
+			let report = format!("solving {} - -", time);
+			assert!(report.parse::<Profile>().is_err(), "{}", report);
+		}
+	}
+
+	#[test]
+	fn total_per_phase() {
+		let first = Profile {
+			phases: vec![phase(Phase::Parsing, 1, Some(1)), phase(Phase::Solving, 10, Some(2))]
+		};
+		let second = Profile {
+			phases: vec![phase(Phase::Parsing, 2, Some(3)), phase(Phase::Solving, 20, None)]
+		};
+		assert_eq!(Profile::total([&first, &second]), Profile {
+			phases: vec![phase(Phase::Parsing, 3, Some(4)), PhaseProfile {
+				phase:       Phase::Solving,
+				time:        Duration::from_millis(30),
+				allocations: None,
+				bytes:       None
+			}]
+		});
+	}
+}
@@ -0,0 +1,359 @@
+//! Property tests: generate random inputs, check an invariant on each,
+//! and shrink the first failing input down to something readable.
+//! ```ignore
+//! property::check(
+//!     |rng| (rng.range(1..=100), rng.range(1..=100)),
+//!     |&(n, m): &(usize, usize)| ensure(placement(n, m).len() == n, || "wrong room count".into())
+//! );
+//! ```
+//! Shrinking can leave the generator's domain (a count of 0, a list longer than allowed),
+//! [`check_where`] takes a predicate for the inputs the property is meant for.
+//! A failure prints the seed, `PROPERTY_SEED=<seed> cargo test` runs the same inputs again.
+//! Without it the seed is always the same, set it to check other inputs.
+
+use std::{
+	env,
+	fmt::Debug,
+	panic::{self, AssertUnwindSafe}
+};
+
+use crate::random::Rng;
+
+pub const SEED_VARIABLE: &str = "PROPERTY_SEED";
+/// The seed when `PROPERTY_SEED` is not set, so `cargo test` checks the same cases every time.
+pub const DEFAULT_SEED: u64 = 0x5eed_0034;
+
+/// Smaller versions of a value, tried in order when shrinking a failing input.
+pub trait Shrink: Sized {
+	fn shrink(&self) -> Vec<Self> {
+		Vec::new()
+	}
+}
+
+macro_rules! shrink_integer {
+	($($t:ty),*) => {$(
+		impl Shrink for $t {
+			// towards zero: zero itself, halfway, one closer
+			fn shrink(&self) -> Vec<Self> {
+				let value = *self;
+				let mut smaller = Vec::new();
+				for candidate in [0, value / 2, value - value.signum()] {
+					if candidate != value && !smaller.contains(&candidate) {
+						smaller.push(candidate);
+					}
+				}
+				smaller
+			}
+		}
+	)*};
+}
+shrink_integer!(i8, i16, i32, i64, isize);
+
+macro_rules! shrink_unsigned {
+	($($t:ty),*) => {$(
+		impl Shrink for $t {
+			fn shrink(&self) -> Vec<Self> {
+				let value = *self;
+				let mut smaller = Vec::new();
+				for candidate in [0, value / 2, value.saturating_sub(1)] {
+					if candidate != value && !smaller.contains(&candidate) {
+						smaller.push(candidate);
+					}
+				}
+				smaller
+			}
+		}
+	)*};
+}
+shrink_unsigned!(u8, u16, u32, u64, usize);
+
+impl Shrink for bool {
+	fn shrink(&self) -> Vec<Self> {
+		if *self {
+			vec![false]
+		} else {
+			Vec::new()
+		}
+	}
+}
+
+impl Shrink for char {}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+	// first drop big parts, then single elements, then shrink the elements themselves
+	fn shrink(&self) -> Vec<Self> {
+		let mut smaller = Vec::new();
+		let length = self.len();
+		if length > 1 {
+			smaller.push(self[..length / 2].to_vec());
+			smaller.push(self[length / 2..].to_vec());
+		}
+		for index in 0..length {
+			let mut without = self.clone();
+			without.remove(index);
+			smaller.push(without);
+		}
+		for index in 0..length {
+			for element in self[index].shrink() {
+				let mut shrunk = self.clone();
+				shrunk[index] = element;
+				smaller.push(shrunk);
+			}
+		}
+		smaller
+	}
+}
+
+impl Shrink for String {
+	fn shrink(&self) -> Vec<Self> {
+		let characters: Vec<char> = self.chars().collect();
+		characters
+			.shrink()
+			.into_iter()
+			.map(|characters| characters.into_iter().collect())
+			.collect()
+	}
+}
+
+impl<T: Shrink + Clone> Shrink for Option<T> {
+	fn shrink(&self) -> Vec<Self> {
+		match self {
+			None => Vec::new(),
+			Some(value) => std::iter::once(None)
+				.chain(value.shrink().into_iter().map(Some))
+				.collect()
+		}
+	}
+}
+
+macro_rules! shrink_tuple {
+	($(($($t:ident $index:tt),*)),*) => {$(
+		impl<$($t: Shrink + Clone),*> Shrink for ($($t,)*) {
+			// one part at a time, the others stay as they are
+			fn shrink(&self) -> Vec<Self> {
+				let mut smaller = Vec::new();
+				$(
+					for part in self.$index.shrink() {
+						let mut shrunk = self.clone();
+						shrunk.$index = part;
+						smaller.push(shrunk);
+					}
+				)*
+				smaller
+			}
+		}
+	)*};
+}
+shrink_tuple!((A 0, B 1), (A 0, B 1, C 2), (A 0, B 1, C 2, D 3));
+
+/// `Ok` if the condition holds, the reason otherwise.
+pub fn ensure(condition: bool, reason: impl FnOnce() -> String) -> Result<(), String> {
+	if condition {
+		Ok(())
+	} else {
+		Err(reason())
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+	pub cases:       usize,
+	pub seed:        u64,
+	/// Inputs tried while shrinking, at most.
+	pub max_shrinks: usize
+}
+impl Default for Config {
+	/// 100 cases, seeded from `PROPERTY_SEED` if it is set and `DEFAULT_SEED` otherwise.
+	fn default() -> Self {
+		Config {
+			cases:       100,
+			seed:        env::var(SEED_VARIABLE)
+				.ok()
+				.and_then(|seed| seed.parse().ok())
+				.unwrap_or(DEFAULT_SEED),
+			max_shrinks: 1000
+		}
+	}
+}
+
+/// Checks the property on generated inputs, with the default config.
+#[track_caller]
+pub fn check<T, G, P>(generate: G, property: P)
+where
+	T: Shrink + Clone + Debug,
+	G: FnMut(&mut Rng) -> T,
+	P: Fn(&T) -> Result<(), String>
+{
+	check_with(Config::default(), generate, |_| true, property)
+}
+
+/// Like [`check`], but shrinking only tries inputs that are `valid`,
+/// which should hold for everything the generator makes.
+#[track_caller]
+pub fn check_where<T, G, V, P>(generate: G, valid: V, property: P)
+where
+	T: Shrink + Clone + Debug,
+	G: FnMut(&mut Rng) -> T,
+	V: Fn(&T) -> bool,
+	P: Fn(&T) -> Result<(), String>
+{
+	check_with(Config::default(), generate, valid, property)
+}
+
+/// The property on one input, where a panic counts as failing.
+fn holds<T, P: Fn(&T) -> Result<(), String>>(property: &P, value: &T) -> Result<(), String> {
+	match panic::catch_unwind(AssertUnwindSafe(|| property(value))) {
+		Ok(result) => result,
+		Err(payload) => Err(match payload.downcast_ref::<&str>() {
+			Some(message) => format!("panicked: {}", message),
+			None => match payload.downcast_ref::<String>() {
+				Some(message) => format!("panicked: {}", message),
+				None => "panicked".to_string()
+			}
+		})
+	}
+}
+
+#[track_caller]
+pub fn check_with<T, G, V, P>(config: Config, mut generate: G, valid: V, property: P)
+where
+	T: Shrink + Clone + Debug,
+	G: FnMut(&mut Rng) -> T,
+	V: Fn(&T) -> bool,
+	P: Fn(&T) -> Result<(), String>
+{
+	let mut rng = Rng::new(config.seed);
+	for case in 1..=config.cases {
+		let value = generate(&mut rng);
+		assert!(valid(&value), "generated an invalid input on case {}: {:?}", case, value);
+		let Err(reason) = holds(&property, &value) else {
+			continue;
+		};
+		let (minimal, reason, shrinks) =
+			shrink(&property, &valid, value, reason, config.max_shrinks);
+		panic!(
+			"Property failed on case {} (seed {}): {}\nminimal input after {} shrinks: {:?}\n\
+			 rerun with {}={}",
+			case, config.seed, reason, shrinks, minimal, SEED_VARIABLE, config.seed
+		);
+	}
+}
+
+/// Keeps taking the first smaller valid input that still fails, until none do.
+fn shrink<T, V, P>(
+	property: &P,
+	valid: &V,
+	mut value: T,
+	mut reason: String,
+	max_shrinks: usize
+) -> (T, String, usize)
+where
+	T: Shrink,
+	V: Fn(&T) -> bool,
+	P: Fn(&T) -> Result<(), String>
+{
+	let mut tried = 0;
+	let mut shrinks = 0;
+	'shrinking: while tried < max_shrinks {
+		for candidate in value.shrink().into_iter().filter(valid) {
+			tried += 1;
+			if let Err(new_reason) = holds(property, &candidate) {
+				value = candidate;
+				reason = new_reason;
+				shrinks += 1;
+				continue 'shrinking;
+			}
+			if tried >= max_shrinks {
+				break 'shrinking;
+			}
+		}
+		break;
+	}
+	(value, reason, shrinks)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::panic;
+
+	use crate::property::{check, check_where, check_with, ensure, shrink, Config, Shrink};
+
+	#[test]
+	fn passing_property() {
+		check(
+			|rng| rng.vec(0..=20, |rng| rng.range(-100..=100i64)),
+			|numbers: &Vec<i64>| {
+				let mut sorted = numbers.clone();
+				sorted.sort();
+				ensure(sorted.len() == numbers.len(), || "sorting lost elements".into())
+			}
+		);
+	}
+
+	#[test]
+	fn shrinks_to_minimal_input() {
+		// fails on any list containing something >= 10
+		let property = |numbers: &Vec<u32>| {
+			ensure(numbers.iter().all(|&n| n < 10), || format!("{:?} has a big number", numbers))
+		};
+		let start = vec![3, 50, 7, 12, 1];
+		let any = |_: &Vec<u32>| true;
+		let (minimal, reason, _) = shrink(&property, &any, start, String::new(), 1000);
+		assert_eq!(minimal, vec![10]);
+		assert_eq!(reason, "[10] has a big number");
+	}
+
+	#[test]
+	fn panics_count_as_failures() {
+		let property = |&(a, b): &(u32, u32)| ensure(a / b < 1000, || "too big".into());
+		let any = |_: &(u32, u32)| true;
+		let (minimal, reason, _) = shrink(&property, &any, (7, 0), String::new(), 1000);
+		assert_eq!(minimal, (0, 0));
+		assert!(reason.starts_with("panicked"));
+	}
+
+	#[test]
+	fn shrinks_only_to_valid_inputs() {
+		// n = 0 would panic, but the generator never makes it
+		let valid = |&(n, m): &(u32, u32)| n >= 1 && m >= n;
+		let property = |&(n, m): &(u32, u32)| ensure(m / n < 50, || "too many per room".into());
+		let (minimal, reason, _) = shrink(&property, &valid, (3, 200), String::new(), 1000);
+		assert_eq!(minimal, (1, 50));
+		assert_eq!(reason, "too many per room");
+		check_where(
+			|rng| {
+				let n = rng.range(1..=10);
+				(n, rng.range(n..=10))
+			},
+			valid,
+			property
+		);
+	}
+
+	#[test]
+	fn failure_reports_seed() {
+		let config = Config {
+			cases:       100,
+			seed:        1234,
+			max_shrinks: 1000
+		};
+		let failure = panic::catch_unwind(|| {
+			check_with(config, |rng| rng.range(0..=1000u64), |_| true, |&n| {
+				ensure(n < 500, || "too big".into())
+			})
+		})
+		.unwrap_err();
+		let message = failure.downcast_ref::<String>().unwrap();
+		assert!(message.contains("seed 1234"), "{}", message);
+		assert!(message.contains("after") && message.contains(": 500\n"), "{}", message);
+	}
+
+	#[test]
+	fn shrink_candidates() {
+		assert_eq!(10u8.shrink(), vec![0, 5, 9]);
+		assert_eq!((-3i32).shrink(), vec![0, -1, -2]);
+		assert_eq!(0usize.shrink(), Vec::<usize>::new());
+		assert_eq!("ab".to_string().shrink()[..2], ["a".to_string(), "b".to_string()]);
+		assert_eq!(Some(1u8).shrink(), vec![None, Some(0)]);
+	}
+}
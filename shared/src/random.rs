@@ -0,0 +1,190 @@
+//! Reproducible random numbers and generators for test inputs, without any dependencies.
+
+use std::{
+	ops::RangeInclusive,
+	time::{SystemTime, UNIX_EPOCH}
+};
+
+/// SplitMix64: tiny, fast, and good enough for generating tests.
+/// The same seed always gives the same numbers.
+#[derive(Debug, Clone)]
+pub struct Rng {
+	state: u64
+}
+
+impl Rng {
+	pub fn new(seed: u64) -> Self {
+		Rng { state: seed }
+	}
+
+	/// A seed that is different every run, to be printed so the run can be repeated.
+	pub fn time_seed() -> u64 {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.unwrap_or_default();
+		Rng::new(now.as_nanos() as u64).next_u64()
+	}
+
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// Uniform in the (inclusive) range, like `rng.range(1..=n)`.
+	pub fn range<T: Uniform>(&mut self, range: RangeInclusive<T>) -> T {
+		T::sample(self, range)
+	}
+
+	/// In [0, 1).
+	pub fn float(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/// True with the given probability.
+	pub fn chance(&mut self, probability: f64) -> bool {
+		self.float() < probability
+	}
+
+	pub fn choose<'a, T>(&mut self, options: &'a [T]) -> &'a T {
+		&options[self.range(0..=options.len() - 1)]
+	}
+
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		for index in (1..items.len()).rev() {
+			items.swap(index, self.range(0..=index));
+		}
+	}
+
+	/// A `Vec` with a length in the range, every element made by `element`.
+	pub fn vec<T>(
+		&mut self,
+		length: RangeInclusive<usize>,
+		mut element: impl FnMut(&mut Rng) -> T
+	) -> Vec<T> {
+		let length = self.range(length);
+		(0..length).map(|_| element(self)).collect()
+	}
+
+	/// A string with a length in the range, made of the characters in `alphabet`.
+	pub fn string(&mut self, length: RangeInclusive<usize>, alphabet: &str) -> String {
+		let alphabet: Vec<char> = alphabet.chars().collect();
+		self.vec(length, |rng| *rng.choose(&alphabet)).into_iter().collect()
+	}
+
+	/// Rows of characters from `alphabet`, like the boards in knightjump.
+	pub fn grid(&mut self, rows: usize, columns: usize, alphabet: &str) -> Vec<String> {
+		(0..rows)
+			.map(|_| self.string(columns..=columns, alphabet))
+			.collect()
+	}
+
+	/// The edges of a random tree on the nodes `0..n`, in random order.
+	pub fn tree(&mut self, n: usize) -> Vec<(usize, usize)> {
+		let mut order: Vec<usize> = (0..n).collect();
+		self.shuffle(&mut order);
+		let mut edges: Vec<_> = (1..n)
+			.map(|index| (order[self.range(0..=index - 1)], order[index]))
+			.collect();
+		self.shuffle(&mut edges);
+		edges
+	}
+
+	/// `m` distinct undirected edges between the nodes `0..n`, without self loops.
+	/// Panics if there are not that many.
+	pub fn graph(&mut self, n: usize, m: usize) -> Vec<(usize, usize)> {
+		assert!(m <= n * n.saturating_sub(1) / 2, "{} nodes cannot have {} edges", n, m);
+		let mut seen = std::collections::HashSet::new();
+		let mut edges = Vec::with_capacity(m);
+		while edges.len() < m {
+			let (a, b) = (self.range(0..=n - 1), self.range(0..=n - 1));
+			if a != b && seen.insert((a.min(b), a.max(b))) {
+				edges.push((a, b));
+			}
+		}
+		edges
+	}
+}
+
+/// Numbers that can be drawn uniformly from a range.
+pub trait Uniform: Sized {
+	fn sample(rng: &mut Rng, range: RangeInclusive<Self>) -> Self;
+}
+
+macro_rules! uniform {
+	($($t:ty),*) => {$(
+		impl Uniform for $t {
+			fn sample(rng: &mut Rng, range: RangeInclusive<Self>) -> Self {
+				let (low, high) = range.into_inner();
+				assert!(low <= high, "empty range {}..={}", low, high);
+				let span = (high as i128 - low as i128) as u128 + 1;
+				// the modulo bias is at most span / 2^64, which does not matter for tests
+				(low as i128 + (rng.next_u64() as u128 % span) as i128) as $t
+			}
+		}
+	)*};
+}
+uniform!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+#[cfg(test)]
+mod tests {
+	use crate::random::Rng;
+
+	#[test]
+	fn same_seed_same_numbers() {
+		let mut a = Rng::new(7);
+		let mut b = Rng::new(7);
+		for _ in 0..100 {
+			assert_eq!(a.next_u64(), b.next_u64());
+		}
+		assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+	}
+
+	#[test]
+	fn ranges_are_inclusive() {
+		let mut rng = Rng::new(1);
+		let mut seen = [false; 6];
+		for _ in 0..1000 {
+			let value: i32 = rng.range(-2..=3);
+			seen[(value + 2) as usize] = true;
+		}
+		assert_eq!(seen, [true; 6]);
+		assert_eq!(rng.range(u64::MAX..=u64::MAX), u64::MAX);
+		// the whole range does not overflow
+		rng.range(i64::MIN..=i64::MAX);
+	}
+
+	#[test]
+	fn tree_is_connected() {
+		let mut rng = Rng::new(3);
+		let n = 50;
+		let edges = rng.tree(n);
+		assert_eq!(edges.len(), n - 1);
+		// union everything, everything should end up together
+		let mut parent: Vec<usize> = (0..n).collect();
+		fn find(parent: &mut Vec<usize>, x: usize) -> usize {
+			if parent[x] != x {
+				parent[x] = find(parent, parent[x]);
+			}
+			parent[x]
+		}
+		for (a, b) in edges {
+			let (a, b) = (find(&mut parent, a), find(&mut parent, b));
+			assert_ne!(a, b, "a tree has no cycles");
+			parent[a] = b;
+		}
+	}
+
+	#[test]
+	fn graph_and_grid_shapes() {
+		let mut rng = Rng::new(4);
+		let edges = rng.graph(5, 10);
+		assert_eq!(edges.len(), 10);
+		assert!(edges.iter().all(|(a, b)| a != b));
+		let grid = rng.grid(3, 4, ".#");
+		assert_eq!(grid.len(), 3);
+		assert!(grid.iter().all(|row| row.len() == 4 && row.chars().all(|c| ".#".contains(c))));
+	}
+}
@@ -0,0 +1,298 @@
+use std::{
+	error::Error,
+	fmt::{Display, Formatter},
+	str::FromStr
+};
+
+use crate::diagnostics;
+
+/// 1-indexed place in the input, for error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+	pub line:   usize,
+	pub column: usize
+}
+impl Display for Position {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "line {}, column {}", self.line, self.column)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+	pub position: Position,
+	pub message:  String
+}
+impl Display for ParseError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.position, self.message)
+	}
+}
+impl Error for ParseError {}
+
+/// Reads tokens and lines out of the whole input.
+/// Newlines are never skipped implicitly: tokens are read from the current
+/// line only, and moving on to the next line is done with `end_line`.
+/// That way a missing or extra value is reported where it happened,
+/// instead of somewhere further down the input.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+	input:      &'a str,
+	offset:     usize, // byte offset into input
+	line:       usize, // 1-indexed
+	column:     usize  // 1-indexed, in characters, kept up to date so long lines stay linear
+}
+
+impl<'a> Scanner<'a> {
+	pub fn new(input: &'a str) -> Self {
+		diagnostics::set_input(input);
+		Scanner {
+			input,
+			offset: 0,
+			line: 1,
+			column: 1
+		}
+	}
+
+	pub fn input(&self) -> &'a str {
+		self.input
+	}
+
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	pub fn position(&self) -> Position {
+		Position {
+			line:   self.line,
+			column: self.column
+		}
+	}
+
+	pub fn error(&self, message: impl Into<String>) -> ParseError {
+		self.error_at(self.position(), message)
+	}
+
+	pub fn error_at(&self, position: Position, message: impl Into<String>) -> ParseError {
+		ParseError {
+			position,
+			message: message.into()
+		}
+	}
+
+	fn remaining(&self) -> &'a str {
+		&self.input[self.offset..]
+	}
+
+	fn advance(&mut self, bytes: usize) {
+		for &byte in self.input.as_bytes()[self.offset..self.offset + bytes].iter() {
+			if byte == b'\n' {
+				self.line += 1;
+				self.column = 1;
+			} else if byte & 0b1100_0000 != 0b1000_0000 {
+				// the first byte of a character, the others continue it
+				self.column += 1;
+			}
+		}
+		self.offset += bytes;
+	}
+
+	/// Skips spaces and tabs (and the '\r' of a CRLF), but stays on this line.
+	fn skip_inline_whitespace(&mut self) {
+		let skipped = self
+			.remaining()
+			.bytes()
+			.take_while(|byte| matches!(byte, b' ' | b'\t' | b'\r'))
+			.count();
+		self.advance(skipped);
+	}
+
+	/// Skips all whitespace, newlines included.
+	pub fn skip_whitespace(&mut self) {
+		let skipped = self
+			.remaining()
+			.bytes()
+			.take_while(|byte| byte.is_ascii_whitespace())
+			.count();
+		self.advance(skipped);
+	}
+
+	/// Nothing but whitespace is left on the current line.
+	pub fn at_line_end(&mut self) -> bool {
+		self.skip_inline_whitespace();
+		self.remaining().is_empty() || self.remaining().starts_with('\n')
+	}
+
+	/// Nothing but whitespace is left in the input.
+	pub fn at_end(&self) -> bool {
+		self.remaining().trim().is_empty()
+	}
+
+	/// Next whitespace separated token on the current line.
+	pub fn token_str(&mut self) -> Result<&'a str, ParseError> {
+		self.skip_inline_whitespace();
+		diagnostics::set_offset(self.offset);
+		let length = self
+			.remaining()
+			.find(|c: char| c.is_ascii_whitespace())
+			.unwrap_or(self.remaining().len());
+		if length == 0 {
+			return Err(self.error(if self.remaining().is_empty() {
+				"expected a value, found end of input"
+			} else {
+				"expected a value, found end of line"
+			}));
+		}
+		let token = &self.remaining()[..length];
+		self.advance(length);
+		Ok(token)
+	}
+
+	pub fn token<T>(&mut self) -> Result<T, ParseError>
+	where
+		T: FromStr,
+		T::Err: Display
+	{
+		self.skip_inline_whitespace();
+		let start = self.position();
+		let token = self.token_str()?;
+		token.parse().map_err(|e| {
+			self.error_at(
+				start,
+				format!(
+					"could not read `{}` as {}: {}",
+					token,
+					std::any::type_name::<T>(),
+					e
+				)
+			)
+		})
+	}
+
+	/// The rest of the current line, without surrounding whitespace.
+	/// Stops before the newline itself.
+	pub fn line(&mut self) -> Result<&'a str, ParseError> {
+		diagnostics::set_offset(self.offset);
+		if self.remaining().is_empty() {
+			return Err(self.error("expected a line, found end of input"));
+		}
+		let length = self.remaining().find('\n').unwrap_or(self.remaining().len());
+		let line = &self.remaining()[..length];
+		self.advance(length);
+		Ok(line.trim())
+	}
+
+	/// The rest of the current line without moving past it, without surrounding whitespace.
+	pub fn peek_line(&self) -> &'a str {
+		let length = self.remaining().find('\n').unwrap_or(self.remaining().len());
+		self.remaining()[..length].trim()
+	}
+
+	/// Checks that the current line has nothing more on it, then moves to the next one.
+	pub fn end_line(&mut self) -> Result<(), ParseError> {
+		self.skip_inline_whitespace();
+		diagnostics::set_offset(self.offset);
+		match self.remaining().chars().next() {
+			Some('\n') => {
+				self.advance(1);
+				Ok(())
+			},
+			Some(_) => Err(self.error(format!(
+				"expected end of line, found `{}`",
+				self.remaining().split_whitespace().next().unwrap_or_default()
+			))),
+			None => Err(self.error("expected another line, found end of input"))
+		}
+	}
+
+	/// Checks that only whitespace is left.
+	pub fn finish(&mut self) -> Result<(), ParseError> {
+		self.skip_whitespace();
+		if self.remaining().is_empty() {
+			Ok(())
+		} else {
+			Err(self.error(format!(
+				"expected end of input, found `{}`",
+				self.remaining().split_whitespace().next().unwrap_or_default()
+			)))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::scanner::{Position, Scanner};
+
+	#[test]
+	fn tokens_stay_on_their_line() {
+		let mut scanner = Scanner::new("3 5\nleft_oar\n");
+		assert_eq!(scanner.token::<usize>(), Ok(3));
+		assert_eq!(scanner.token::<usize>(), Ok(5));
+		let error = scanner.token::<usize>().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 4 });
+		scanner.end_line().unwrap();
+		assert_eq!(scanner.token_str(), Ok("left_oar"));
+		assert!(scanner.at_line_end());
+		scanner.finish().unwrap();
+	}
+
+	#[test]
+	fn errors_point_at_the_token() {
+		let mut scanner = Scanner::new("1 2\n3 x4 5\n");
+		scanner.line().unwrap();
+		scanner.end_line().unwrap();
+		scanner.token::<usize>().unwrap();
+		let error = scanner.token::<usize>().unwrap_err();
+		assert_eq!(error.position, Position { line: 2, column: 3 });
+		assert!(error.message.contains("`x4`"), "{}", error);
+	}
+
+	#[test]
+	fn columns_count_characters() {
+		let mut scanner = Scanner::new("é ø x\n");
+		assert_eq!(scanner.token_str(), Ok("é"));
+		assert_eq!(scanner.token_str(), Ok("ø"));
+		let error = scanner.token::<usize>().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 5 });
+	}
+
+	#[test]
+	fn long_lines_stay_fast() {
+		// a column counted from the start of the line for every token would take minutes
+		let items = 200_000;
+		let line: Vec<String> = (0..items).map(|item| item.to_string()).collect();
+		let input = format!("{} x\n", line.join(" "));
+		let mut scanner = Scanner::new(&input);
+		for item in 0..items {
+			assert_eq!(scanner.token::<usize>(), Ok(item));
+		}
+		let error = scanner.token::<usize>().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: input.len() - 1 });
+	}
+
+	#[test]
+	fn end_line_rejects_leftovers() {
+		let mut scanner = Scanner::new("1 2\n");
+		scanner.token::<usize>().unwrap();
+		let error = scanner.end_line().unwrap_err();
+		assert_eq!(error.position, Position { line: 1, column: 3 });
+	}
+
+	#[test]
+	fn crlf_is_whitespace() {
+		let mut scanner = Scanner::new("4 2\r\n..K.\r\n");
+		assert_eq!(scanner.token::<usize>(), Ok(4));
+		assert_eq!(scanner.token::<usize>(), Ok(2));
+		scanner.end_line().unwrap();
+		assert_eq!(scanner.line(), Ok("..K."));
+		scanner.finish().unwrap();
+	}
+
+	#[test]
+	fn finish_rejects_leftovers() {
+		let mut scanner = Scanner::new("1\n2\n");
+		scanner.token::<usize>().unwrap();
+		let error = scanner.finish().unwrap_err();
+		assert_eq!(error.position, Position { line: 2, column: 1 });
+	}
+}
@@ -0,0 +1,398 @@
+//! Shortest paths over states that are only known through their neighbors,
+//! like the squares a knight can jump to, or a position together with the keys picked up so far.
+//!
+//! ```ignore
+//! let search = bfs(
+//!     [knight],
+//!     |&square| board.neighbors(square, &KNIGHT).filter(|&next| !board[next]),
+//!     |&square| square == Point::new(0, 0)
+//! );
+//! let steps = search.distance_to_goal(); // `None` when the corner cannot be reached
+//! ```
+//! `astar` finds the same distances to a goal with fewer states expanded, given a heuristic that
+//! never guesses more than the real distance, like `knight_distance` for knight jumps.
+
+use std::{
+	cmp::Reverse,
+	collections::{BinaryHeap, HashMap, VecDeque},
+	hash::Hash
+};
+
+use crate::grid::Point;
+
+/// What a search found: the distance to every state it reached and how it got there.
+#[derive(Debug, Clone)]
+pub struct Search<S> {
+	distances: HashMap<S, usize>,
+	// the state every reached state was first reached from, starts have none
+	parents:   HashMap<S, S>,
+	goal:      Option<S>,
+	expanded:  usize
+}
+
+/// Breadth first search from all `starts` at once, until a state is a `goal` or every reachable
+/// state is found. Pass `|_| false` as the goal for the distances to everything.
+pub fn bfs<S, N>(
+	starts: impl IntoIterator<Item = S>,
+	mut neighbors: impl FnMut(&S) -> N,
+	mut goal: impl FnMut(&S) -> bool
+) -> Search<S>
+where
+	S: Clone + Eq + Hash,
+	N: IntoIterator<Item = S>
+{
+	let mut search = Search {
+		distances: HashMap::new(),
+		parents:   HashMap::new(),
+		goal:      None,
+		expanded:  0
+	};
+	let mut queue = VecDeque::new();
+	for start in starts {
+		if !search.distances.contains_key(&start) {
+			search.distances.insert(start.clone(), 0);
+			queue.push_back(start);
+		}
+	}
+	while let Some(state) = queue.pop_front() {
+		if goal(&state) {
+			search.goal = Some(state);
+			break;
+		}
+		let distance = search.distances[&state];
+		search.expanded += 1;
+		for next in neighbors(&state) {
+			if !search.distances.contains_key(&next) {
+				search.distances.insert(next.clone(), distance + 1);
+				search.parents.insert(next.clone(), state.clone());
+				queue.push_back(next);
+			}
+		}
+	}
+	search
+}
+
+/// Best first search from `start` to the closest `goal`, where `neighbors` gives the states one
+/// step away with the cost of that step. The `heuristic` guesses the cost left to a goal,
+/// and has to be at most the real cost for the answer to be right.
+/// Only the distance to the goal is known to be the shortest, others may be too long.
+pub fn astar<S, N>(
+	start: S,
+	mut neighbors: impl FnMut(&S) -> N,
+	mut heuristic: impl FnMut(&S) -> usize,
+	mut goal: impl FnMut(&S) -> bool
+) -> Search<S>
+where
+	S: Clone + Eq + Hash,
+	N: IntoIterator<Item = (S, usize)>
+{
+	let mut search = Search {
+		distances: HashMap::from([(start.clone(), 0)]),
+		parents:   HashMap::new(),
+		goal:      None,
+		expanded:  0
+	};
+	// by the guessed length of a path through the state, and then the furthest along first;
+	// the number only keeps the heap from comparing states, which need not be `Ord`
+	let mut queue = BinaryHeap::new();
+	let mut states = vec![start];
+	queue.push(Reverse((heuristic(&states[0]), Reverse(0), 0)));
+	while let Some(Reverse((_, Reverse(distance), index))) = queue.pop() {
+		let state = states[index].clone();
+		if search.distances[&state] != distance {
+			continue; // a shorter way to this state was found since
+		}
+		if goal(&state) {
+			search.goal = Some(state);
+			break;
+		}
+		search.expanded += 1;
+		for (next, cost) in neighbors(&state) {
+			let next_distance = distance + cost;
+			if search.distances.get(&next).is_none_or(|&old| next_distance < old) {
+				search.distances.insert(next.clone(), next_distance);
+				search.parents.insert(next.clone(), state.clone());
+				let guess = next_distance + heuristic(&next);
+				queue.push(Reverse((guess, Reverse(next_distance), states.len())));
+				states.push(next);
+			}
+		}
+	}
+	search
+}
+
+/// Steps left, right, up and down between two points.
+pub fn manhattan(from: Point, to: Point) -> usize {
+	from.x.abs_diff(to.x) + from.y.abs_diff(to.y)
+}
+
+/// Steps in any of the 8 directions between two points.
+pub fn chebyshev(from: Point, to: Point) -> usize {
+	from.x.abs_diff(to.x).max(from.y.abs_diff(to.y))
+}
+
+/// Knight jumps between two points on an empty board without edges.
+/// A board that is smaller or has blocked squares can only take more.
+pub fn knight_distance(from: Point, to: Point) -> usize {
+	let (dx, dy) = (from.x.abs_diff(to.x) as isize, from.y.abs_diff(to.y) as isize);
+	let (x, y) = (dx.max(dy), dx.min(dy));
+	match (x, y) {
+		(1, 0) => 3,
+		(2, 2) => 4,
+		_ => {
+			let delta = x - y;
+			// the known closed form, with the two exceptions above close to the start
+			if y > delta {
+				(delta - 2 * (delta - y).div_euclid(3)) as usize
+			} else {
+				(delta - 2 * (delta - y).div_euclid(4)) as usize
+			}
+		}
+	}
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+	/// `None` if the state was not reached (before the goal, if there is one).
+	pub fn distance(&self, state: &S) -> Option<usize> {
+		self.distances.get(state).copied()
+	}
+
+	pub fn distances(&self) -> &HashMap<S, usize> {
+		&self.distances
+	}
+
+	/// The first goal that was reached, one of the closest.
+	pub fn goal(&self) -> Option<&S> {
+		self.goal.as_ref()
+	}
+
+	pub fn distance_to_goal(&self) -> Option<usize> {
+		self.goal.as_ref().and_then(|goal| self.distance(goal))
+	}
+
+	/// The state the search came to `state` from, `None` for the starts.
+	pub fn parent(&self, state: &S) -> Option<&S> {
+		self.parents.get(state)
+	}
+
+	/// A shortest path from one of the starts to `state`, both included.
+	pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+		self.distances.get(state)?;
+		let mut path = vec![state.clone()];
+		while let Some(parent) = self.parents.get(path.last().unwrap()) {
+			path.push(parent.clone());
+		}
+		path.reverse();
+		Some(path)
+	}
+
+	pub fn path_to_goal(&self) -> Option<Vec<S>> {
+		self.path_to(self.goal.as_ref()?)
+	}
+
+	/// How many states had their neighbors looked at.
+	pub fn expanded(&self) -> usize {
+		self.expanded
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		graph::{weighted::WeightedGraph, Direction},
+		grid::{Grid, Point, ADJACENT, KNIGHT, ORTHOGONAL},
+		property::{self, ensure, Shrink},
+		random::Rng,
+		search::{astar, bfs, chebyshev, knight_distance, manhattan}
+	};
+
+	fn maze(map: &str) -> (Grid<char>, Vec<Point>) {
+		let mut starts = Vec::new();
+		let grid = Grid::parse(map, |point, c| {
+			if c == 'S' {
+				starts.push(point);
+			}
+			Ok(c)
+		})
+		.unwrap();
+		(grid, starts)
+	}
+
+	#[test]
+	fn knight_on_grid() {
+		// knightjump sample 1, the corner is 2 jumps away
+		let (board, _) = maze("....\n....\n....\n...K\n");
+		let knight = Point::new(3, 3);
+		let search = bfs(
+			[knight],
+			|&square| board.neighbors(square, &KNIGHT),
+			|&square| square == Point::new(0, 0)
+		);
+		assert_eq!(search.distance_to_goal(), Some(2));
+		let path = search.path_to_goal().unwrap();
+		assert_eq!((path[0], path[2]), (knight, Point::new(0, 0)));
+		for jump in path.windows(2) {
+			assert!(KNIGHT.contains(&(jump[1] - jump[0])), "{:?}", path);
+		}
+		assert_eq!(search.parent(&knight), None);
+	}
+
+	#[test]
+	fn multiple_starts() {
+		let (grid, starts) = maze("S...#...S\n....#....\n");
+		let search = bfs(
+			starts,
+			|&point| {
+				grid.neighbors(point, &ORTHOGONAL)
+					.filter(|&next| grid[next] != '#')
+			},
+			|_| false
+		);
+		assert_eq!(search.goal(), None);
+		// every open square is as far as its closest start
+		assert_eq!(search.distance(&Point::new(3, 1)), Some(4));
+		assert_eq!(search.distance(&Point::new(5, 1)), Some(4));
+		assert_eq!(search.distance(&Point::new(4, 0)), None);
+		assert_eq!(search.distances().len(), 16);
+		assert_eq!(search.path_to(&Point::new(8, 0)), Some(vec![Point::new(8, 0)]));
+	}
+
+	#[test]
+	fn keys_and_doors() {
+		// the door `A` only opens with the key `a`, so the way to the end goes past the key first
+		let (grid, starts) = maze("########\n#a.S.A.E\n########\n");
+		let grid = &grid;
+		let search = bfs(
+			[(starts[0], false)],
+			|&(point, key)| {
+				grid.neighbors(point, &ORTHOGONAL)
+					.filter(move |&next| match grid[next] {
+						'#' => false,
+						'A' => key,
+						_ => true
+					})
+					.map(move |next| (next, key || grid[next] == 'a'))
+					.collect::<Vec<_>>()
+			},
+			|&(point, _)| grid[point] == 'E'
+		);
+		assert_eq!(search.distance_to_goal(), Some(2 + 6));
+		let path = search.path_to_goal().unwrap();
+		assert_eq!(path.len(), 9);
+		assert_eq!(path[2], (Point::new(1, 1), true));
+		assert_eq!(search.distance(&(Point::new(6, 1), false)), None);
+	}
+
+	#[test]
+	fn unreachable_goal() {
+		// counting up by 2 from 1 never gets to an even number
+		let search = bfs([1_u32], |&n| (n < 20).then_some(n + 2), |&n| n % 2 == 0);
+		assert_eq!(search.goal(), None);
+		assert_eq!(search.path_to_goal(), None);
+		assert_eq!(search.distance(&21), Some(10));
+		assert_eq!(search.path_to(&7), Some(vec![1, 3, 5, 7]));
+	}
+
+	#[test]
+	fn knight_distance_is_exact_on_open_boards() {
+		let board = Grid::new(41, 41, ());
+		let center = Point::new(20, 20);
+		let search = bfs([center], |&square| board.neighbors(square, &KNIGHT), |_| false);
+		// far enough from the edges that they never get in the way
+		for (square, _) in board.iter().filter(|&(square, _)| chebyshev(square, center) <= 10) {
+			assert_eq!(
+				Some(knight_distance(square, center)),
+				search.distance(&square),
+				"from {}",
+				square - center
+			);
+		}
+	}
+
+	#[derive(Debug, Clone)]
+	struct Maze {
+		open:   Grid<bool>,
+		start:  Point,
+		target: Point
+	}
+	impl Shrink for Maze {
+		// open one square at a time
+		fn shrink(&self) -> Vec<Self> {
+			self.open
+				.iter()
+				.filter(|(_, &open)| !open)
+				.map(|(square, _)| {
+					let mut maze = self.clone();
+					maze.open[square] = true;
+					maze
+				})
+				.collect()
+		}
+	}
+	impl Maze {
+		fn generate(rng: &mut Rng) -> Self {
+			let size = rng.range(1..=12);
+			let mut square = || {
+				let mut coordinate = || rng.range(0..=size as isize - 1);
+				Point::new(coordinate(), coordinate())
+			};
+			let (start, target) = (square(), square());
+			let mut open = Grid::from_fn(size, size, |_| rng.chance(0.7));
+			open[start] = true;
+			open[target] = true;
+			Maze {
+				open,
+				start,
+				target
+			}
+		}
+	}
+
+	#[test]
+	fn astar_matches_bfs() {
+		type Heuristic = fn(Point, Point) -> usize;
+		let heuristics: [(&[Point], Heuristic); 3] =
+			[(&ORTHOGONAL, manhattan), (&ADJACENT, chebyshev), (&KNIGHT, knight_distance)];
+		for (offsets, heuristic) in heuristics {
+			property::check(Maze::generate, |Maze { open, start, target }| {
+				let neighbors = |&square: &Point| {
+					open.neighbors(square, offsets)
+						.filter(|&next| open[next])
+						.collect::<Vec<_>>()
+				};
+				let goal = |square: &Point| square == target;
+				let bfs = bfs([*start], neighbors, goal);
+				let astar = astar(
+					*start,
+					|square| neighbors(square).into_iter().map(|next| (next, 1)),
+					|&square| heuristic(square, *target),
+					goal
+				);
+				ensure(astar.distance_to_goal() == bfs.distance_to_goal(), || {
+					format!("A* found {:?}", astar.distance_to_goal())
+				})?;
+				let path = astar.path_to_goal().unwrap_or_default();
+				ensure(path.len() == bfs.distance_to_goal().map_or(0, |steps| steps + 1), || {
+					format!("A* took the path {:?}", path)
+				})?;
+				// a heuristic that is never too high cannot make A* look at more than BFS does
+				ensure(astar.expanded() <= bfs.expanded(), || {
+					format!("A* expanded {}, BFS {}", astar.expanded(), bfs.expanded())
+				})
+			});
+		}
+	}
+
+	#[test]
+	fn astar_with_costs() {
+		// to 100 from 1 by adding 1 for 1 or doubling for 3, without a heuristic it is Dijkstra
+		let steps = |&n: &usize| [(n + 1, 1), (n * 2, 3)].into_iter().filter(|&(n, _)| n <= 100);
+		let search = astar(1, steps, |_| 0, |&n| n == 100);
+		let edges =
+			(1..=100).flat_map(|n| steps(&n).map(move |(next, cost)| (n, next, cost as i64)));
+		let dijkstra = WeightedGraph::from_edges(101, Direction::Directed, edges).dijkstra(1);
+		assert_eq!(search.distance_to_goal().map(|cost| cost as i64), dijkstra.distance(100));
+		assert_eq!(search.path_to_goal(), dijkstra.path_to(100));
+		assert!(search.expanded() < 100);
+	}
+}
@@ -0,0 +1,283 @@
+//! Several solvers for one problem, picked at run time and compared against each other.
+//!
+//! A problem lists its variants, the first one is the default:
+//! ```ignore
+//! const SOLVERS: &[Solver] = &[
+//!     Solver { name: "search", solve: search },
+//!     Solver { name: "flood", solve: flood }
+//! ];
+//! ```
+//! `solvers::run(SOLVERS, settings)` solves stdin with the one named by `--solver <name>`
+//! or the `SOLVER` environment variable (`judge --solver <name>` sets it).
+//! `differential` runs all of them on the same inputs, and reports where they disagree
+//! and how fast each one was, usually from a test on the samples and generated inputs:
+//! `cargo test solvers_agree -- --nocapture`.
+
+use std::{
+	env,
+	fmt::{Display, Formatter},
+	io::{stdin, Read},
+	panic::{self, AssertUnwindSafe},
+	time::{Duration, Instant}
+};
+
+use crate::{
+	diagnostics,
+	driver::{with_stack, Settings},
+	judge::compare,
+	normalize::normalize,
+	verdict::Verdict
+};
+
+pub const SOLVER_VARIABLE: &str = "SOLVER";
+
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+	pub name:  &'static str,
+	pub solve: fn(&str) -> String
+}
+
+/// The solver with the name, or the first one if no name is given.
+pub fn choose<'a>(solvers: &'a [Solver], name: Option<&str>) -> Result<&'a Solver, String> {
+	let Some(name) = name else {
+		return solvers.first().ok_or_else(|| "There are no solvers!".to_string());
+	};
+	solvers
+		.iter()
+		.find(|solver| solver.name == name)
+		.ok_or_else(|| {
+			let names: Vec<_> = solvers.iter().map(|solver| solver.name).collect();
+			format!("No solver named `{}`, there are: {}", name, names.join(", "))
+		})
+}
+
+/// The name given with `--solver <name>`, or else in `SOLVER`.
+pub fn requested() -> Option<String> {
+	env::args()
+		.skip_while(|argument| argument != "--solver")
+		.nth(1)
+		.or_else(|| env::var(SOLVER_VARIABLE).ok())
+}
+
+/// Like `driver::run`, with the requested solver.
+pub fn run(solvers: &[Solver], settings: Settings) {
+	diagnostics::install();
+	let solver = choose(solvers, requested().as_deref()).unwrap_or_else(|e| panic!("{}", e));
+	let mut input = String::new();
+	stdin()
+		.read_to_string(&mut input)
+		.expect("Could not read from stdin()?");
+	let input = normalize(&input, settings.whitespace);
+	let solve = solver.solve;
+	println!("{}", with_stack(settings.stack_size, move || solve(&input)));
+}
+
+/// An input on which the outputs are not all the same (as judged by `judge::compare`).
+#[derive(Debug, Clone)]
+pub struct Disagreement {
+	pub name:    String,
+	pub input:   String,
+	/// The output of every solver, in order.
+	pub outputs: Vec<(&'static str, String)>
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+	pub inputs:        usize,
+	/// Time spent by every solver on all inputs together, in order.
+	pub times:         Vec<(&'static str, Duration)>,
+	pub disagreements: Vec<Disagreement>
+}
+
+/// Runs every solver on every (named) input, comparing the outputs to those of the first solver.
+/// A panic counts as an output, so a crashing solver shows up as a disagreement.
+pub fn differential(
+	solvers: &[Solver],
+	inputs: &[(String, String)],
+	settings: Settings
+) -> Report {
+	let mut times: Vec<_> = solvers.iter().map(|solver| (solver.name, Duration::ZERO)).collect();
+	let mut disagreements = Vec::new();
+	for (name, input) in inputs {
+		let outputs: Vec<_> = solvers
+			.iter()
+			.zip(times.iter_mut())
+			.map(|(solver, (_, total))| {
+				let (output, time) = timed(solver, input, settings);
+				*total += time;
+				(solver.name, output)
+			})
+			.collect();
+		let expected = &outputs[0].1;
+		if outputs
+			.iter()
+			.any(|(_, output)| compare(expected, output) != Verdict::Accepted)
+		{
+			disagreements.push(Disagreement {
+				name: name.clone(),
+				input: input.clone(),
+				outputs
+			});
+		}
+	}
+	Report {
+		inputs: inputs.len(),
+		times,
+		disagreements
+	}
+}
+
+fn timed(solver: &Solver, input: &str, settings: Settings) -> (String, Duration) {
+	let solve = solver.solve;
+	let input = input.to_string();
+	let run = panic::catch_unwind(AssertUnwindSafe(|| {
+		with_stack(settings.stack_size, move || {
+			let start = Instant::now();
+			let output = solve(&input);
+			(output, start.elapsed())
+		})
+	}));
+	run.unwrap_or_else(|payload| {
+		let message = match payload.downcast_ref::<&str>() {
+			Some(message) => message.to_string(),
+			None => match payload.downcast_ref::<String>() {
+				Some(message) => message.clone(),
+				None => "(no message)".to_string()
+			}
+		};
+		(format!("panicked: {}", message), Duration::ZERO)
+	})
+}
+
+impl Report {
+	/// Panics with the report if any solvers disagree.
+	#[track_caller]
+	pub fn assert_agreement(&self) {
+		assert!(self.disagreements.is_empty(), "Solvers disagree!\n{}", self);
+	}
+}
+
+// long inputs and outputs are cut off after this many lines
+const SHOWN_LINES: usize = 10;
+
+fn indented(text: &str) -> String {
+	let mut lines: Vec<_> = text.trim_end().lines().take(SHOWN_LINES + 1).collect();
+	if lines.len() > SHOWN_LINES {
+		lines[SHOWN_LINES] = "...";
+	}
+	lines
+		.iter()
+		.map(|line| format!("    | {}\n", line))
+		.collect()
+}
+
+impl Display for Report {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{} solvers on {} inputs:", self.times.len(), self.inputs)?;
+		let fastest = self
+			.times
+			.iter()
+			.map(|(_, time)| *time)
+			.min()
+			.unwrap_or_default()
+			.max(Duration::from_nanos(1));
+		for (name, time) in self.times.iter() {
+			writeln!(
+				f,
+				"  {}: {:.3}ms ({:.2}x)",
+				name,
+				time.as_secs_f64() * 1000.0,
+				time.as_secs_f64() / fastest.as_secs_f64()
+			)?;
+		}
+		writeln!(f, "{} disagreements", self.disagreements.len())?;
+		for disagreement in self.disagreements.iter() {
+			writeln!(f, "  on {}:", disagreement.name)?;
+			write!(f, "{}", indented(&disagreement.input))?;
+			let (first, expected) = &disagreement.outputs[0];
+			for (name, output) in disagreement.outputs.iter() {
+				if name == first {
+					writeln!(f, "  {}:", name)?;
+				} else {
+					match compare(expected, output) {
+						Verdict::Accepted => {
+							writeln!(f, "  {}: same as {}", name, first)?;
+							continue;
+						},
+						Verdict::WrongAnswer(reason) => writeln!(f, "  {}: {}", name, reason)?
+					}
+				}
+				write!(f, "{}", indented(output))?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		driver::Settings,
+		solvers::{choose, differential, Solver}
+	};
+
+	fn sum(input: &str) -> String {
+		input
+			.split_whitespace()
+			.map(|n| n.parse::<i64>().unwrap())
+			.sum::<i64>()
+			.to_string()
+	}
+
+	// forgets the last number
+	fn broken_sum(input: &str) -> String {
+		let numbers: Vec<i64> = input.split_whitespace().map(|n| n.parse().unwrap()).collect();
+		numbers[..numbers.len() - 1].iter().sum::<i64>().to_string()
+	}
+
+	const SOLVERS: &[Solver] = &[
+		Solver {
+			name:  "sum",
+			solve: sum
+		},
+		Solver {
+			name:  "broken",
+			solve: broken_sum
+		},
+	];
+
+	fn inputs(inputs: &[&str]) -> Vec<(String, String)> {
+		inputs
+			.iter()
+			.enumerate()
+			.map(|(index, input)| (format!("input #{}", index + 1), input.to_string()))
+			.collect()
+	}
+
+	#[test]
+	fn choosing() {
+		assert_eq!(choose(SOLVERS, None).unwrap().name, "sum");
+		assert_eq!(choose(SOLVERS, Some("broken")).unwrap().name, "broken");
+		let unknown = choose(SOLVERS, Some("fast")).unwrap_err();
+		assert!(unknown.contains("sum, broken"), "{}", unknown);
+		assert!(choose(&[], None).is_err());
+	}
+
+	#[test]
+	fn finds_disagreements() {
+		let report = differential(SOLVERS, &inputs(&["1 2 0", "3 4", ""]), Settings::default());
+		assert_eq!(report.inputs, 3);
+		let names: Vec<_> = report.disagreements.iter().map(|d| d.name.as_str()).collect();
+		assert_eq!(names, ["input #2", "input #3"]);
+		let shown = report.to_string();
+		assert!(shown.contains("broken: expected `7`, found `3`"), "{}", shown);
+		assert!(shown.contains("panicked"), "{}", shown);
+	}
+
+	#[test]
+	fn agreeing_solvers() {
+		let report = differential(&SOLVERS[..1], &inputs(&["1 2", "3"]), Settings::default());
+		report.assert_agreement();
+		assert!(report.to_string().starts_with("1 solvers on 2 inputs:\n  sum: "));
+	}
+}
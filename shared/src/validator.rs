@@ -0,0 +1,95 @@
+//! Output validators, for problems where more than one output is correct.
+//!
+//! A problem registers one by implementing [`Validator`] in `src/validator.rs`,
+//! which is used by its sample tests (`#[cfg(test)] mod validator;` in main.rs)
+//! and by a `src/bin/validator.rs` that the local judge runs instead of comparing tokens:
+//! ```ignore
+//! #[path = "../validator.rs"]
+//! mod validator;
+//!
+//! fn main() {
+//!     shared::validator::run::<validator::OutputValidator>()
+//! }
+//! ```
+
+use std::io::{stdin, Read};
+
+use crate::{
+	judge,
+	verdict::{test_case_files, Verdict}
+};
+
+pub trait Validator {
+	/// Checks the contestant output of a test case.
+	/// The answer is the one from the answer file, which is just one of the correct outputs.
+	fn validate(input: &str, answer: &str, output: &str) -> Verdict;
+}
+
+/// What is used when a problem has no validator: the answer is the only correct output,
+/// up to whitespace and float precision.
+pub struct Exact;
+impl Validator for Exact {
+	fn validate(_input: &str, answer: &str, output: &str) -> Verdict {
+		judge::compare(answer, output)
+	}
+}
+
+/// The whole of a validator binary: called as `validator <input> <answer> [feedback directory]`
+/// with the contestant output on stdin, exits with the verdict.
+pub fn run<V: Validator>() -> ! {
+	let (input, answer) = test_case_files().expect("Validator needs the test case files!");
+	let mut output = String::new();
+	if stdin().read_to_string(&mut output).is_err() {
+		Verdict::WrongAnswer("output is not valid UTF-8".to_string()).exit()
+	}
+	V::validate(&input, &answer, &output).exit()
+}
+
+/// For sample tests: panics with the reason unless the output is accepted.
+#[track_caller]
+pub fn assert_accepted<V: Validator>(input: &str, answer: &str, output: &str) {
+	if let Verdict::WrongAnswer(reason) = V::validate(input, answer, output) {
+		panic!("Wrong Answer: {}\n--- OUTPUT:\n{}", reason, output);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		validator::{assert_accepted, Exact, Validator},
+		verdict::Verdict
+	};
+
+	// any permutation of the numbers in the input
+	struct Permutation;
+	impl Validator for Permutation {
+		fn validate(input: &str, _answer: &str, output: &str) -> Verdict {
+			let mut expected: Vec<_> = input.split_whitespace().collect();
+			let mut actual: Vec<_> = output.split_whitespace().collect();
+			expected.sort();
+			actual.sort();
+			if expected == actual {
+				Verdict::Accepted
+			} else {
+				Verdict::WrongAnswer("not a permutation of the input".to_string())
+			}
+		}
+	}
+
+	#[test]
+	fn exact_compares_with_answer() {
+		assert_eq!(Exact::validate("", "1 2", "1\n2\n"), Verdict::Accepted);
+		assert!(matches!(Exact::validate("", "1 2", "2 1"), Verdict::WrongAnswer(_)));
+	}
+
+	#[test]
+	fn custom_validator() {
+		assert_accepted::<Permutation>("1 2 3", "1 2 3", "3 1 2");
+	}
+
+	#[test]
+	#[should_panic(expected = "not a permutation")]
+	fn assert_accepted_panics_with_reason() {
+		assert_accepted::<Permutation>("1 2 3", "1 2 3", "3 1 1");
+	}
+}
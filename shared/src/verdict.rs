@@ -0,0 +1,56 @@
+use std::{
+	env, fs,
+	io::{self, Error, ErrorKind},
+	process::exit
+};
+
+// exit codes from the Kattis problem package format,
+// used by interactors (and output validators) to report back to the judge
+pub const EXIT_ACCEPTED: i32 = 42;
+pub const EXIT_WRONG_ANSWER: i32 = 43;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+	Accepted,
+	WrongAnswer(String) // reason
+}
+
+impl Verdict {
+	/// Ends the process the way the judge expects, with the reason on stderr.
+	pub fn exit(self) -> ! {
+		match self {
+			Verdict::Accepted => exit(EXIT_ACCEPTED),
+			Verdict::WrongAnswer(reason) => {
+				eprintln!("{}", reason);
+				exit(EXIT_WRONG_ANSWER)
+			}
+		}
+	}
+
+	/// What a judging process reported, `None` if it did not exit with one of the verdict codes.
+	pub fn from_exit_code(code: Option<i32>, stderr: &str) -> Option<Verdict> {
+		match code {
+			Some(EXIT_ACCEPTED) => Some(Verdict::Accepted),
+			Some(EXIT_WRONG_ANSWER) => Some(Verdict::WrongAnswer(stderr.trim().to_string())),
+			_ => None
+		}
+	}
+}
+
+/// The input and answer files of the test case, as interactors and output validators
+/// are called with `<program> <input> <answer> [feedback directory]` (the Kattis argument order).
+pub fn test_case_files() -> io::Result<(String, String)> {
+	let mut arguments = env::args().skip(1);
+	let mut read = |what: &str| {
+		let path = arguments.next().ok_or_else(|| {
+			Error::new(
+				ErrorKind::InvalidInput,
+				format!("needs the {} file as an argument", what)
+			)
+		})?;
+		fs::read_to_string(path)
+	};
+	let input = read("input")?;
+	let answer = read("answer")?;
+	Ok((input, answer))
+}
@@ -0,0 +1,239 @@
+//! Pictures of a grid search, one frame per step, to see how a BFS spreads over a board.
+//!
+//! ```ignore
+//! let mut replay = Replay::new();
+//! // after every BFS layer:
+//! replay.push(Frame::from_fn(format!("{} jumps", layer), n, n, |row, column| cell(row, column)));
+//! replay.show("sample_1"); // target/visual/sample_1.txt
+//! ```
+//! Boards kept in a `Grid` become frames through `Frame::from_grid`.
+//! With `VISUAL=ansi` the frames are animated in the terminal (on stderr) instead, shaded by
+//! distance; with `VISUAL=<directory>` the text frames go to that directory.
+//! Like `dot`, nothing is shown in release builds.
+
+use std::{
+	fmt::Write as _,
+	io::{self, Write},
+	path::PathBuf,
+	thread,
+	time::Duration
+};
+
+use crate::grid::{Grid, Point};
+
+pub const VARIABLE: &str = "VISUAL";
+
+/// Time between two frames of an animation.
+pub const FRAME_DELAY: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+	/// Drawn as is, like `#` and `.`.
+	Plain(char),
+	/// Drawn highlighted, like the `K` of the knight.
+	Marked(char),
+	/// A visited cell, by its distance from the start.
+	Distance(usize)
+}
+
+impl Cell {
+	/// Distances as `0`-`9` then `a`-`z`, anything further away as `+`.
+	pub fn symbol(self) -> char {
+		match self {
+			Cell::Plain(c) | Cell::Marked(c) => c,
+			Cell::Distance(distance) => char::from_digit(distance as u32, 36)
+				.filter(|_| distance < 36)
+				.unwrap_or('+')
+		}
+	}
+
+	fn ansi(self, out: &mut String) {
+		// blue (close) over green to red (far away), from the 256 color palette
+		const SHADES: [u8; 16] = [
+			21, 27, 33, 39, 45, 51, 50, 48, 46, 82, 154, 190, 226, 214, 202, 196
+		];
+		let _ = match self {
+			Cell::Plain(c) => write!(out, "{}", c),
+			Cell::Marked(c) => write!(out, "\x1b[1;7m{}\x1b[0m", c),
+			Cell::Distance(distance) => write!(
+				out,
+				"\x1b[30;48;5;{}m{}\x1b[0m",
+				SHADES[distance.min(SHADES.len() - 1)],
+				self.symbol()
+			)
+		};
+	}
+}
+
+/// The whole board at one step of the search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+	pub title: String,
+	pub rows:  Vec<Vec<Cell>>
+}
+
+impl Frame {
+	pub fn new(title: impl Into<String>, rows: Vec<Vec<Cell>>) -> Self {
+		Frame {
+			title: title.into(),
+			rows
+		}
+	}
+
+	/// A frame of `rows` by `columns` cells, asking for every cell by its row and column.
+	pub fn from_fn(
+		title: impl Into<String>,
+		rows: usize,
+		columns: usize,
+		mut cell: impl FnMut(usize, usize) -> Cell
+	) -> Self {
+		let rows = (0..rows)
+			.map(|row| (0..columns).map(|column| cell(row, column)).collect())
+			.collect();
+		Frame::new(title, rows)
+	}
+
+	/// A frame of the whole grid, asking for every cell by its point and value.
+	pub fn from_grid<T>(
+		title: impl Into<String>,
+		grid: &Grid<T>,
+		mut cell: impl FnMut(Point, &T) -> Cell
+	) -> Self {
+		let cells = grid.map(|point, value| cell(point, value));
+		Frame::new(title, cells.rows().take(grid.height()).map(<[Cell]>::to_vec).collect())
+	}
+
+	/// The title, then one line per row.
+	pub fn text(&self) -> String {
+		let mut text = format!("{}\n", self.title);
+		for row in self.rows.iter() {
+			text.extend(row.iter().map(|cell| cell.symbol()));
+			text.push('\n');
+		}
+		text
+	}
+
+	/// Like `text`, with colors and highlights as ANSI escape codes.
+	pub fn ansi(&self) -> String {
+		let mut ansi = format!("\x1b[1m{}\x1b[0m\n", self.title);
+		for row in self.rows.iter() {
+			for cell in row.iter() {
+				cell.ansi(&mut ansi);
+			}
+			ansi.push('\n');
+		}
+		ansi
+	}
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+	pub frames: Vec<Frame>
+}
+
+impl Replay {
+	pub fn new() -> Self {
+		Replay::default()
+	}
+
+	pub fn push(&mut self, frame: Frame) -> &mut Self {
+		self.frames.push(frame);
+		self
+	}
+
+	/// Every frame as text, with an empty line between them.
+	pub fn text(&self) -> String {
+		self.frames.iter().map(Frame::text).collect::<Vec<_>>().join("\n")
+	}
+
+	/// Draws the frames over each other, waiting `delay` after every frame.
+	pub fn animate(&self, out: &mut impl Write, delay: Duration) -> io::Result<()> {
+		for frame in self.frames.iter() {
+			// clear the screen and start at the top left
+			write!(out, "\x1b[2J\x1b[H{}", frame.ansi())?;
+			out.flush()?;
+			thread::sleep(delay);
+		}
+		Ok(())
+	}
+
+	/// Animates the replay if `VISUAL` is `ansi`, otherwise writes `<name>.txt` to the directory
+	/// in `VISUAL`, or to `target/visual` in the current directory (the crate, in tests).
+	/// Returns the file written, and does nothing in release builds.
+	pub fn show(&self, name: &str) -> io::Result<Option<PathBuf>> {
+		if !cfg!(debug_assertions) {
+			return Ok(None);
+		}
+		let directory = match std::env::var_os(VARIABLE) {
+			Some(variable) if variable == "ansi" => {
+				self.animate(&mut io::stderr().lock(), FRAME_DELAY)?;
+				return Ok(None);
+			},
+			Some(directory) => PathBuf::from(directory),
+			None => PathBuf::from("target/visual")
+		};
+		std::fs::create_dir_all(&directory)?;
+		let path = directory.join(format!("{}.txt", name));
+		std::fs::write(&path, self.text())?;
+		Ok(Some(path))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use crate::{
+		grid::{Grid, Point},
+		visual::{Cell, Frame, Replay}
+	};
+
+	fn layer(distance: usize) -> Frame {
+		Frame::from_fn(format!("{} jumps", distance), 2, 3, |row, column| {
+			match (row, column) {
+				(0, 0) => Cell::Marked('K'),
+				(1, 2) => Cell::Plain('#'),
+				(row, column) if row + column <= distance => Cell::Distance(row + column),
+				_ => Cell::Plain('.')
+			}
+		})
+	}
+
+	#[test]
+	fn text_frames() {
+		let mut replay = Replay::new();
+		replay.push(layer(0)).push(layer(1));
+		assert_eq!(replay.text(), "0 jumps\nK..\n..#\n\n1 jumps\nK1.\n1.#\n");
+	}
+
+	#[test]
+	fn frame_of_grid() {
+		let grid = Grid::from_fn(3, 2, |point| point.x + point.y);
+		let frame = Frame::from_grid("grid", &grid, |point, &distance| match point {
+			Point { x: 0, y: 0 } => Cell::Marked('K'),
+			Point { x: 2, y: 1 } => Cell::Plain('#'),
+			_ => Cell::Distance(distance as usize)
+		});
+		assert_eq!(frame.text(), "grid\nK12\n12#\n");
+	}
+
+	#[test]
+	fn far_distances() {
+		assert_eq!(Cell::Distance(9).symbol(), '9');
+		assert_eq!(Cell::Distance(35).symbol(), 'z');
+		assert_eq!(Cell::Distance(36).symbol(), '+');
+	}
+
+	#[test]
+	fn ansi_animation() {
+		let mut replay = Replay::new();
+		replay.push(layer(2)).push(layer(3));
+		let mut out = Vec::new();
+		replay.animate(&mut out, Duration::ZERO).unwrap();
+		let out = String::from_utf8(out).unwrap();
+		assert_eq!(out.matches("\x1b[2J\x1b[H").count(), 2);
+		assert!(out.contains("\x1b[1;7mK\x1b[0m"), "{:?}", out);
+		assert!(out.contains("\x1b[30;48;5;27m1\x1b[0m"), "{:?}", out);
+		assert!(out.contains("#"), "{:?}", out);
+	}
+}